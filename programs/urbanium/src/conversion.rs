@@ -0,0 +1,319 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::UrbaniumError;
+use crate::fees::{fee_on_raw, fee_on_total};
+use crate::state::Vault;
+
+/// Direction in which a share/asset conversion rounds. Callers pick the
+/// direction that favours the vault: down when the user supplies the input,
+/// up when the user asks for an exact output.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rounding {
+    Down,
+    Up,
+}
+
+pub fn convert_to_shares(vault: &Vault, assets: u64, rounding: Rounding) -> Result<u64> {
+    mul_div(assets, total_shares(vault)?, total_assets(vault)?, rounding)
+}
+
+pub fn convert_to_assets(vault: &Vault, shares: u64, rounding: Rounding) -> Result<u64> {
+    mul_div(shares, total_assets(vault)?, total_shares(vault)?, rounding)
+}
+
+/// Result of a fee-aware conversion: the amount the user receives (or owes)
+/// and the entry/exit fee in assets that stays in the vault.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Quote {
+    pub amount: u64,
+    pub fee: u64,
+}
+
+/// `deposit(assets)`: the entry fee is taken out of `assets` and the rest is
+/// converted to shares, rounded down.
+pub fn quote_deposit(vault: &Vault, assets: u64) -> Result<Quote> {
+    let fee = fee_on_total(assets, vault.deposit_fee_bps)?;
+    let net = assets
+        .checked_sub(fee)
+        .ok_or_else(|| error!(UrbaniumError::MathOverflow))?;
+
+    Ok(Quote {
+        amount: convert_to_shares(vault, net, Rounding::Down)?,
+        fee,
+    })
+}
+
+/// `mint_shares(shares)`: assets owed for `shares`, rounded up, plus the
+/// entry fee on top.
+pub fn quote_mint(vault: &Vault, shares: u64) -> Result<Quote> {
+    let net = convert_to_assets(vault, shares, Rounding::Up)?;
+    let fee = fee_on_raw(net, vault.deposit_fee_bps)?;
+
+    Ok(Quote {
+        amount: net
+            .checked_add(fee)
+            .ok_or_else(|| error!(UrbaniumError::MathOverflow))?,
+        fee,
+    })
+}
+
+/// `withdraw(shares)`: assets for `shares`, rounded down, less an exit fee of
+/// `fee_bps` (see `fees::exit_fee_bps`).
+pub fn quote_redeem(vault: &Vault, shares: u64, fee_bps: u16) -> Result<Quote> {
+    let gross = convert_to_assets(vault, shares, Rounding::Down)?;
+    let fee = fee_on_total(gross, fee_bps)?;
+
+    Ok(Quote {
+        amount: gross
+            .checked_sub(fee)
+            .ok_or_else(|| error!(UrbaniumError::MathOverflow))?,
+        fee,
+    })
+}
+
+/// `withdraw_assets(assets)`: shares burned for `assets` plus an exit fee of
+/// `fee_bps` on top, rounded up.
+pub fn quote_withdraw(vault: &Vault, assets: u64, fee_bps: u16) -> Result<Quote> {
+    let fee = fee_on_raw(assets, fee_bps)?;
+    let gross = assets
+        .checked_add(fee)
+        .ok_or_else(|| error!(UrbaniumError::MathOverflow))?;
+
+    Ok(Quote {
+        amount: convert_to_shares(vault, gross, Rounding::Up)?,
+        fee,
+    })
+}
+
+pub(crate) fn total_shares(vault: &Vault) -> Result<u128> {
+    u128::from(vault.total_shares)
+        .checked_add(vault.virtual_shares())
+        .ok_or_else(|| error!(UrbaniumError::MathOverflow))
+}
+
+pub(crate) fn total_assets(vault: &Vault) -> Result<u128> {
+    u128::from(vault.total_assets)
+        .checked_add(Vault::VIRTUAL_ASSETS)
+        .ok_or_else(|| error!(UrbaniumError::MathOverflow))
+}
+
+fn mul_div(value: u64, numerator: u128, denominator: u128, rounding: Rounding) -> Result<u64> {
+    let product: u128 = u128::from(value)
+        .checked_mul(numerator)
+        .ok_or_else(|| error!(UrbaniumError::MathOverflow))?;
+
+    let mut quotient: u128 = product
+        .checked_div(denominator)
+        .ok_or_else(|| error!(UrbaniumError::MathOverflow))?;
+
+    if rounding == Rounding::Up && quotient * denominator < product {
+        quotient = quotient
+            .checked_add(1)
+            .ok_or_else(|| error!(UrbaniumError::MathOverflow))?;
+    }
+
+    u64::try_from(quotient).map_err(|_| error!(UrbaniumError::MathOverflow))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Vault holding `assets` and `shares`, before the virtual offset.
+    fn vault_holding(assets: u64, shares: u64, decimals_offset: u8) -> Vault {
+        let mut vault = Vault::zeroed();
+        vault.total_assets = assets;
+        vault.total_shares = shares;
+        vault.decimals_offset = decimals_offset;
+        vault
+    }
+
+    /// Applies `deposit(assets)` to the vault totals; returns the shares.
+    fn deposit(vault: &mut Vault, assets: u64) -> u64 {
+        let shares = quote_deposit(vault, assets).unwrap().amount;
+        vault.total_assets += assets;
+        vault.total_shares += shares;
+        shares
+    }
+
+    /// Applies `withdraw(shares)` to the vault totals; returns the assets.
+    fn redeem(vault: &mut Vault, shares: u64) -> u64 {
+        let assets = quote_redeem(vault, shares, 0).unwrap().amount;
+        vault.total_assets -= assets;
+        vault.total_shares -= shares;
+        assets
+    }
+
+    #[test]
+    fn mul_div_rounds_in_the_requested_direction() {
+        assert_eq!(mul_div(10, 1, 3, Rounding::Down).unwrap(), 3);
+        assert_eq!(mul_div(10, 1, 3, Rounding::Up).unwrap(), 4);
+        assert_eq!(mul_div(9, 1, 3, Rounding::Up).unwrap(), 3);
+        assert_eq!(mul_div(0, 5, 7, Rounding::Up).unwrap(), 0);
+    }
+
+    #[test]
+    fn mul_div_overflow_is_an_error() {
+        let overflow = error!(UrbaniumError::MathOverflow);
+        assert_eq!(mul_div(1, 1, 0, Rounding::Down).unwrap_err(), overflow);
+        assert_eq!(
+            mul_div(u64::MAX, u128::MAX, 1, Rounding::Down).unwrap_err(),
+            overflow
+        );
+        assert_eq!(
+            mul_div(u64::MAX, 2, 1, Rounding::Down).unwrap_err(),
+            overflow
+        );
+    }
+
+    #[test]
+    fn empty_vault_converts_at_the_virtual_offset() {
+        let vault = vault_holding(0, 0, 3);
+
+        assert_eq!(
+            convert_to_shares(&vault, 100, Rounding::Down).unwrap(),
+            100_000
+        );
+        assert_eq!(
+            convert_to_assets(&vault, 100_000, Rounding::Down).unwrap(),
+            100
+        );
+        assert_eq!(quote_deposit(&vault, 100).unwrap().amount, 100_000);
+        assert_eq!(quote_mint(&vault, 100_001).unwrap().amount, 101);
+    }
+
+    #[test]
+    fn exact_output_quotes_round_in_the_vaults_favour() {
+        // 10 assets for 3 shares, virtual offset included.
+        let vault = vault_holding(9, 2, 0);
+
+        // One share is worth 3.33 assets: minting it costs 4, redeeming it
+        // pays 3.
+        assert_eq!(quote_mint(&vault, 1).unwrap().amount, 4);
+        assert_eq!(quote_redeem(&vault, 1, 0).unwrap().amount, 3);
+
+        // Four assets are worth 1.2 shares: withdrawing them burns 2,
+        // depositing them mints 1.
+        assert_eq!(quote_withdraw(&vault, 4, 0).unwrap().amount, 2);
+        assert_eq!(quote_deposit(&vault, 4).unwrap().amount, 1);
+    }
+
+    #[test]
+    fn fees_round_up() {
+        assert_eq!(fee_on_raw(1_000, 100).unwrap(), 10);
+        assert_eq!(fee_on_raw(1, 1).unwrap(), 1);
+        assert_eq!(fee_on_raw(1_000, 0).unwrap(), 0);
+        // 1% of the net amount inside 1_010.
+        assert_eq!(fee_on_total(1_010, 100).unwrap(), 10);
+        assert_eq!(fee_on_total(1_011, 100).unwrap(), 11);
+        assert_eq!(fee_on_total(1, 1).unwrap(), 1);
+        assert_eq!(fee_on_total(1_000, 0).unwrap(), 0);
+    }
+
+    #[test]
+    fn quotes_charge_entry_and_exit_fees() {
+        let mut vault = vault_holding(999, 999, 0);
+        vault.deposit_fee_bps = 100;
+
+        assert_eq!(
+            quote_deposit(&vault, 1_010).unwrap(),
+            Quote {
+                amount: 1_000,
+                fee: 10
+            }
+        );
+        assert_eq!(
+            quote_mint(&vault, 1_000).unwrap(),
+            Quote {
+                amount: 1_010,
+                fee: 10
+            }
+        );
+        assert_eq!(
+            quote_redeem(&vault, 1_000, 100).unwrap(),
+            Quote {
+                amount: 990,
+                fee: 10
+            }
+        );
+        assert_eq!(
+            quote_withdraw(&vault, 990, 100).unwrap(),
+            Quote {
+                amount: 1_000,
+                fee: 10
+            }
+        );
+    }
+
+    #[test]
+    fn round_trips_never_favour_the_user() {
+        let vaults = [
+            vault_holding(0, 0, 0),
+            vault_holding(0, 0, 6),
+            vault_holding(9, 2, 0),
+            vault_holding(1_000_003, 999_983, 0),
+            vault_holding(123_456_789, 98_765, 3),
+            vault_holding(7, 1_000_000_007, 9),
+        ];
+
+        for mut vault in vaults {
+            for (deposit_fee_bps, withdraw_fee_bps) in [(0, 0), (30, 50)] {
+                vault.deposit_fee_bps = deposit_fee_bps;
+                for amount in [1, 2, 3, 10, 999, 1_000_001, 12_345_678_901] {
+                    // Assets in, shares out, assets back.
+                    let shares = quote_deposit(&vault, amount).unwrap().amount;
+                    let back = quote_redeem(&vault, shares, withdraw_fee_bps)
+                        .unwrap()
+                        .amount;
+                    assert!(back <= amount, "deposit/redeem {amount}");
+
+                    // Shares minted, then redeemed.
+                    let cost = quote_mint(&vault, amount).unwrap().amount;
+                    let back = quote_redeem(&vault, amount, withdraw_fee_bps)
+                        .unwrap()
+                        .amount;
+                    assert!(back <= cost, "mint/redeem {amount}");
+
+                    // Assets withdrawn: the shares burned are worth at least
+                    // as much, and depositing the assets again mints no more.
+                    let burned = quote_withdraw(&vault, amount, withdraw_fee_bps)
+                        .unwrap()
+                        .amount;
+                    let paid = quote_redeem(&vault, burned, withdraw_fee_bps)
+                        .unwrap()
+                        .amount;
+                    assert!(paid >= amount, "withdraw/redeem {amount}");
+                    assert!(
+                        quote_deposit(&vault, amount).unwrap().amount <= burned,
+                        "withdraw/deposit {amount}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn virtual_offset_defeats_first_depositor_donation() {
+        const DONATION: u64 = 1_000_000_000;
+        const VICTIM: u64 = 1_000_000_000;
+
+        // Without an offset, one share and a donation round the victim's
+        // deposit down to a single share worth two thirds of it.
+        let mut vault = vault_holding(0, 0, 0);
+        deposit(&mut vault, 1);
+        vault.total_assets += DONATION;
+        let victim = deposit(&mut vault, VICTIM);
+        assert_eq!(victim, 1);
+        assert_eq!(redeem(&mut vault, victim), 666_666_667);
+
+        // With `decimals_offset = 6` the victim loses under one part per
+        // million, and the attacker gets back about half of the donation.
+        let mut vault = vault_holding(0, 0, 6);
+        let attacker = deposit(&mut vault, 1);
+        vault.total_assets += DONATION;
+        let victim = deposit(&mut vault, VICTIM);
+        let victim_out = redeem(&mut vault, victim);
+        assert!(victim_out >= VICTIM - VICTIM / 1_000_000, "{victim_out}");
+        assert!(redeem(&mut vault, attacker) < 1 + DONATION / 2 + DONATION / 1_000);
+    }
+}
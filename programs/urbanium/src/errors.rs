@@ -41,6 +41,7 @@ pub enum UrbaniumError {
     #[msg("Oracle confidence interval too large")]
     OracleConfidenceTooHigh,
 
+    /// No longer returned: prices are normalized across exponents.
     #[msg("Oracle exponent mismatch")]
     OracleExponentMismatch,
 
@@ -52,4 +53,109 @@ pub enum UrbaniumError {
 
     #[msg("Shares must be non-zero")]
     ZeroShares,
+
+    #[msg("Deposit amount too small to mint any shares")]
+    ZeroSharesMinted,
+
+    #[msg("Decimals offset exceeds maximum")]
+    InvalidDecimalsOffset,
+
+    #[msg("Shares minted below minimum")]
+    SharesBelowMinimum,
+
+    #[msg("Withdraw amount below minimum")]
+    AmountBelowMinimum,
+
+    #[msg("Assets required exceed maximum")]
+    AssetsAboveMaximum,
+
+    #[msg("Shares burned exceed maximum")]
+    SharesAboveMaximum,
+
+    #[msg("Vault has no share mint")]
+    ShareMintNotEnabled,
+
+    #[msg("Invalid share mint")]
+    InvalidShareMint,
+
+    #[msg("Share token account or user position required")]
+    MissingShareAccount,
+
+    #[msg("Share token account not owned by the position owner")]
+    InvalidShareAccountOwner,
+
+    #[msg("User position still holds shares")]
+    PositionNotEmpty,
+
+    #[msg("Cannot transfer shares to the same position")]
+    SelfShareTransfer,
+
+    #[msg("Invalid delegate")]
+    InvalidDelegate,
+
+    #[msg("Signer is neither the position owner nor its delegate")]
+    UnauthorizedWithdrawer,
+
+    #[msg("Delegate allowance exceeded")]
+    DelegateAllowanceExceeded,
+
+    #[msg("Beneficiary must be the depositor")]
+    InvalidBeneficiary,
+
+    #[msg("Fee exceeds maximum")]
+    InvalidFeeBps,
+
+    #[msg("Fee recipient position cannot be closed")]
+    FeePositionNotClosable,
+
+    #[msg("Deposit exceeds vault total assets cap")]
+    VaultDepositCapExceeded,
+
+    #[msg("Deposit exceeds per-user assets cap")]
+    UserDepositCapExceeded,
+
+    #[msg("Withdraw request is not at the head of the queue")]
+    WithdrawRequestNotNext,
+
+    #[msg("Withdrawal within the minimum holding period")]
+    MinHoldPeriodActive,
+
+    #[msg("Mock oracle requires a test-oracle build")]
+    MockOracleDisabled,
+
+    #[msg("Oracle feed does not publish an EMA price")]
+    OracleEmaUnavailable,
+
+    #[msg("Too many secondary oracles or invalid oracle quorum")]
+    InvalidOracleConfig,
+
+    #[msg("Oracle feed account missing or out of order")]
+    InvalidOracleFeed,
+
+    #[msg("Too few oracle feeds passed validation")]
+    OracleQuorumNotMet,
+
+    #[msg("Oracle feeds diverge beyond the configured bound")]
+    OracleDivergenceTooHigh,
+
+    #[msg("Oracle confidence interval straddles the routing threshold")]
+    RoutePriceAmbiguous,
+
+    #[msg("Routing band must contain the routing threshold")]
+    InvalidRouteBand,
+
+    #[msg("Minimum holding period cannot be enforced on tokenized shares")]
+    MinHoldWithShareMint,
+
+    #[msg("User position required")]
+    MissingUserPosition,
+
+    #[msg("Per-user deposit cap cannot be enforced on tokenized shares")]
+    UserCapWithShareMint,
+
+    #[msg("Vault account version is not supported by this program")]
+    UnsupportedVaultVersion,
+
+    #[msg("Tokenized vaults mint share tokens instead of crediting a user position")]
+    UserPositionWithShareMint,
 }
@@ -0,0 +1,231 @@
+use anchor_lang::event::EVENT_IX_TAG_LE;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::Event;
+
+use crate::oracle::{OracleKind, PriceSource};
+use crate::pda;
+
+/// The self-CPI `emit_cpi!` performs, for helpers that run outside the
+/// instruction handler and have no `ctx` to read the event authority from.
+pub(crate) fn emit_cpi_event<E: Event>(
+    event_authority: &AccountInfo,
+    event_authority_bump: u8,
+    event: &E,
+) -> Result<()> {
+    let data: Vec<u8> = EVENT_IX_TAG_LE.into_iter().chain(event.data()).collect();
+    let ix = Instruction::new_with_bytes(
+        crate::ID,
+        &data,
+        vec![AccountMeta::new_readonly(event_authority.key(), true)],
+    );
+
+    invoke_signed(
+        &ix,
+        core::slice::from_ref(event_authority),
+        &[&[pda::EVENT_AUTHORITY_SEED, &[event_authority_bump]]],
+    )
+    .map_err(Into::into)
+}
+
+/// Emitted by `initialize_vault`.
+#[event]
+pub struct VaultInitialized {
+    pub vault: Pubkey,
+    pub mint: Pubkey,
+    pub creator: Pubkey,
+    /// `Pubkey::default()` for untokenized vaults.
+    pub share_mint: Pubkey,
+    pub oracle_program: Pubkey,
+    pub oracle_feed: Pubkey,
+    pub oracle_kind: OracleKind,
+    pub price_source: PriceSource,
+    /// Exponent of `oracle_feed` at initialization.
+    pub oracle_expo: i32,
+    pub route_threshold_price: i128,
+    pub route_lower_price: i128,
+    pub route_upper_price: i128,
+    pub conservative_routing: bool,
+    pub decimals_offset: u8,
+    pub fee_recipient: Pubkey,
+}
+
+/// Emitted by `deposit`, `deposit_for` and `mint_shares`.
+#[event]
+pub struct Deposited {
+    pub vault: Pubkey,
+    pub depositor: Pubkey,
+    /// Position owner credited with the shares.
+    pub owner: Pubkey,
+    /// Assets transferred in, entry fee included.
+    pub assets: u64,
+    pub shares: u64,
+    /// Entry fee in assets, kept by the vault.
+    pub fee: u64,
+    /// Whether the shares were minted as share tokens.
+    pub tokenized: bool,
+    pub pre_total_assets: u64,
+    pub pre_total_shares: u64,
+    pub post_total_assets: u64,
+    pub post_total_shares: u64,
+}
+
+/// Emitted by `withdraw` and `withdraw_assets`.
+#[event]
+pub struct Withdrawn {
+    pub vault: Pubkey,
+    pub withdrawer: Pubkey,
+    pub owner: Pubkey,
+    pub shares: u64,
+    /// Assets paid out, net of the exit fee.
+    pub assets: u64,
+    /// Exit fee in assets, early-exit penalty included, kept by the vault.
+    pub fee: u64,
+    /// Payout drawn from each vault token account, in liquidity order.
+    pub from_vault_token_account: u64,
+    pub from_yield_token_account_a: u64,
+    pub from_yield_token_account_b: u64,
+    pub pre_total_assets: u64,
+    pub pre_total_shares: u64,
+    pub post_total_assets: u64,
+    pub post_total_shares: u64,
+}
+
+/// Emitted by `route_yield`.
+#[event]
+pub struct YieldRouted {
+    pub vault: Pubkey,
+    pub amount: u64,
+    /// `yield_token_account_a` or `yield_token_account_b`.
+    pub destination: Pubkey,
+    pub price_source: PriceSource,
+    /// Price selected by `price_source`, and its confidence, in
+    /// `oracle::PRICE_DECIMALS` decimals.
+    pub oracle_price: i128,
+    pub oracle_conf: u128,
+    pub oracle_publish_time: i64,
+    pub route_threshold_price: i128,
+    pub pre_total_assets: u64,
+    pub pre_total_shares: u64,
+    pub post_total_assets: u64,
+    pub post_total_shares: u64,
+}
+
+/// Emitted by `request_withdraw`.
+#[event]
+pub struct WithdrawRequested {
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    /// Queue id of the new `WithdrawRequest`.
+    pub id: u64,
+    pub shares: u64,
+    /// Assets queued for `claim_withdraw`, net of the exit fee.
+    pub assets: u64,
+    /// Exit fee in assets, early-exit penalty included, kept by the vault.
+    pub fee: u64,
+    /// Whether the shares were burned as share tokens.
+    pub tokenized: bool,
+    pub pre_total_assets: u64,
+    pub pre_total_shares: u64,
+    pub post_total_assets: u64,
+    pub post_total_shares: u64,
+}
+
+/// Emitted by `claim_withdraw`.
+#[event]
+pub struct WithdrawClaimed {
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    pub id: u64,
+    pub assets: u64,
+    /// Payout drawn from each vault token account, in liquidity order.
+    pub from_vault_token_account: u64,
+    pub from_yield_token_account_a: u64,
+    pub from_yield_token_account_b: u64,
+}
+
+/// Emitted by `donate`.
+#[event]
+pub struct Donated {
+    pub vault: Pubkey,
+    pub donor: Pubkey,
+    pub amount: u64,
+    pub pre_total_assets: u64,
+    pub post_total_assets: u64,
+}
+
+/// Emitted by `sync_total_assets`, including when the total is unchanged.
+#[event]
+pub struct TotalAssetsSynced {
+    pub vault: Pubkey,
+    /// Combined token account balance, net of `queued_assets`.
+    pub balance: u64,
+    pub pre_total_assets: u64,
+    pub post_total_assets: u64,
+}
+
+/// Emitted by `transfer_shares`.
+#[event]
+pub struct SharesTransferred {
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    pub recipient: Pubkey,
+    pub shares: u64,
+    /// Sender's average entry price, scaled by `PRICE_SCALE`, carried over
+    /// to the recipient.
+    pub entry_price: u128,
+}
+
+/// Emitted whenever fee accrual mints fee shares: by `accrue_fees` and by the
+/// accrual step of `deposit`, `deposit_for`, `mint_shares`, `withdraw`,
+/// `withdraw_assets`, `request_withdraw` and `route_yield`, ahead of that
+/// instruction's own event.
+#[event]
+pub struct FeesAccrued {
+    pub vault: Pubkey,
+    /// Owner of the position credited with `fee_shares`.
+    pub fee_recipient: Pubkey,
+    pub fee_shares: u64,
+    /// Fees in assets, before conversion into `fee_shares`.
+    pub management_fee: u64,
+    pub performance_fee: u64,
+    /// High-water mark after the accrual, scaled by `PRICE_SCALE`.
+    pub high_water_mark: u128,
+}
+
+/// Emitted by `approve_delegate`.
+#[event]
+pub struct DelegateApproved {
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    pub delegate: Pubkey,
+    /// Allowance, replacing any previous one.
+    pub shares: u64,
+}
+
+/// Emitted by `revoke_delegate`.
+#[event]
+pub struct DelegateRevoked {
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    /// Delegate removed; `Pubkey::default()` if none was set.
+    pub delegate: Pubkey,
+}
+
+/// Emitted by `close_position`, and by `withdraw` when `close_if_empty`
+/// closes the position.
+#[event]
+pub struct PositionClosed {
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+}
+
+/// Emitted by `tokenize_position`.
+#[event]
+pub struct PositionTokenized {
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    /// Shares moved from the position into share tokens.
+    pub shares: u64,
+}
@@ -0,0 +1,418 @@
+use anchor_lang::prelude::*;
+
+use crate::conversion::{total_assets, total_shares};
+use crate::errors::UrbaniumError;
+use crate::events::{emit_cpi_event, FeesAccrued};
+use crate::state::{UserPosition, Vault};
+
+pub const BPS_DENOMINATOR: u128 = 10_000;
+pub const SECONDS_PER_YEAR: u128 = 365 * 24 * 60 * 60;
+
+/// Fixed-point scale of `Vault.high_water_mark`.
+pub const PRICE_SCALE: u128 = 1_000_000_000_000_000_000;
+
+/// Assets per share (virtual offset included), scaled by `PRICE_SCALE`.
+pub fn share_price(vault: &Vault) -> Result<u128> {
+    total_assets(vault)?
+        .checked_mul(PRICE_SCALE)
+        .ok_or_else(|| error!(UrbaniumError::MathOverflow))?
+        .checked_div(total_shares(vault)?)
+        .ok_or_else(|| error!(UrbaniumError::MathOverflow))
+}
+
+/// Fees charged by one `accrue_fees` call.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FeeAccrual {
+    /// Shares added to `vault.total_shares` for the fee recipient.
+    pub fee_shares: u64,
+    /// Fees in assets, before conversion into `fee_shares`.
+    pub management_fee: u64,
+    pub performance_fee: u64,
+}
+
+/// Accrues management and performance fees up to `now` and adds the fee
+/// shares to `vault.total_shares`. Returns the fees charged; the caller
+/// credits `fee_shares` to the fee recipient's position.
+///
+/// The management fee charges `management_fee_bps` per year of elapsed time,
+/// at most one year per accrual. The performance fee charges
+/// `performance_fee_bps` of the gain above the high-water mark. Fee shares
+/// are priced so existing holders are diluted by exactly the fee assets,
+/// rounded in their favour.
+pub fn accrue_fees(vault: &mut Vault, now: i64) -> Result<FeeAccrual> {
+    let elapsed = u128::try_from(now.saturating_sub(vault.last_fee_accrual))
+        .unwrap_or_default()
+        .min(SECONDS_PER_YEAR);
+    vault.last_fee_accrual = vault.last_fee_accrual.max(now);
+
+    let price = share_price(vault)?;
+
+    // Nothing to charge an empty vault; start measuring performance from the
+    // price the first depositor enters at.
+    if vault.total_shares == 0 {
+        vault.high_water_mark = price;
+        return Ok(FeeAccrual::default());
+    }
+
+    let assets = total_assets(vault)?;
+    let supply = total_shares(vault)?;
+
+    let management_fee = assets
+        .checked_mul(u128::from(vault.management_fee_bps))
+        .and_then(|x| x.checked_mul(elapsed))
+        .ok_or_else(|| error!(UrbaniumError::MathOverflow))?
+        / (BPS_DENOMINATOR * SECONDS_PER_YEAR);
+
+    let performance_fee = if price > vault.high_water_mark {
+        // (price - hwm) * supply stays below assets * PRICE_SCALE.
+        let gain = (price - vault.high_water_mark)
+            .checked_mul(supply)
+            .ok_or_else(|| error!(UrbaniumError::MathOverflow))?
+            / PRICE_SCALE;
+
+        gain.checked_mul(u128::from(vault.performance_fee_bps))
+            .ok_or_else(|| error!(UrbaniumError::MathOverflow))?
+            / BPS_DENOMINATOR
+    } else {
+        0
+    };
+
+    let fee_assets = management_fee
+        .checked_add(performance_fee)
+        .ok_or_else(|| error!(UrbaniumError::MathOverflow))?;
+
+    let fee_shares: u128 = if fee_assets == 0 {
+        0
+    } else {
+        let remaining_assets = assets
+            .checked_sub(fee_assets)
+            .filter(|remaining| *remaining > 0)
+            .ok_or_else(|| error!(UrbaniumError::MathOverflow))?;
+
+        fee_assets
+            .checked_mul(supply)
+            .ok_or_else(|| error!(UrbaniumError::MathOverflow))?
+            / remaining_assets
+    };
+
+    let fee_shares = to_u64(fee_shares)?;
+
+    vault.total_shares = vault
+        .total_shares
+        .checked_add(fee_shares)
+        .ok_or_else(|| error!(UrbaniumError::MathOverflow))?;
+
+    vault.high_water_mark = vault.high_water_mark.max(share_price(vault)?);
+
+    Ok(FeeAccrual {
+        fee_shares,
+        management_fee: to_u64(management_fee)?,
+        performance_fee: to_u64(performance_fee)?,
+    })
+}
+
+fn to_u64(value: u128) -> Result<u64> {
+    u64::try_from(value).map_err(|_| error!(UrbaniumError::MathOverflow))
+}
+
+/// Accrues pending fees up to `now`, credits the fee shares to the fee
+/// recipient's position and emits `FeesAccrued` when any were minted. Pass
+/// the instruction's own position as `user_position` when it may be the fee
+/// position: Anchor then holds two copies of the account and writes
+/// `user_position` back last, so the shares are credited to that copy
+/// instead.
+pub fn accrue_and_credit_fees<'info>(
+    vault: &mut Account<'info, Vault>,
+    fee_position: &mut Account<'info, UserPosition>,
+    user_position: Option<&mut Account<'info, UserPosition>>,
+    event_authority: &AccountInfo<'info>,
+    event_authority_bump: u8,
+    now: i64,
+) -> Result<()> {
+    let accrual = accrue_fees(vault, now)?;
+
+    match user_position {
+        Some(user_position) if user_position.key() == fee_position.key() => {
+            credit_fee_shares(user_position, accrual.fee_shares)?
+        }
+        _ => credit_fee_shares(fee_position, accrual.fee_shares)?,
+    }
+
+    if accrual.fee_shares == 0 {
+        return Ok(());
+    }
+
+    let event = FeesAccrued {
+        vault: vault.key(),
+        fee_recipient: vault.fee_recipient,
+        fee_shares: accrual.fee_shares,
+        management_fee: accrual.management_fee,
+        performance_fee: accrual.performance_fee,
+        high_water_mark: vault.high_water_mark,
+    };
+    emit_cpi_event(event_authority, event_authority_bump, &event)
+}
+
+/// Credits accrued fee shares to the fee recipient's position.
+pub fn credit_fee_shares(fee_position: &mut UserPosition, fee_shares: u64) -> Result<()> {
+    fee_position.record_acquired(fee_shares, 0)?;
+    fee_position.shares = fee_position
+        .shares
+        .checked_add(fee_shares)
+        .ok_or_else(|| error!(UrbaniumError::MathOverflow))?;
+
+    Ok(())
+}
+
+/// Shares of `position` still inside the vault's minimum holding period.
+/// Fails with `MissingUserPosition` when the vault has one and no position
+/// is supplied, so omitting the position cannot skip it.
+pub fn locked_shares(vault: &Vault, position: Option<&UserPosition>, now: i64) -> Result<u64> {
+    if vault.min_hold_seconds == 0 {
+        return Ok(0);
+    }
+
+    let Some(position) = position else {
+        return err!(UrbaniumError::MissingUserPosition);
+    };
+
+    Ok(position.locked_shares_at(now))
+}
+
+/// Exit fee for burning `shares` from `position` now: `withdraw_fee_bps`,
+/// plus `early_exit_penalty_bps` when its unlocked shares do not cover them.
+/// Without a penalty, such withdrawals fail with `MinHoldPeriodActive`.
+pub fn exit_fee_bps(
+    vault: &Vault,
+    position: Option<&UserPosition>,
+    shares: u64,
+    now: i64,
+) -> Result<u16> {
+    let locked = locked_shares(vault, position, now)?;
+    let held = position.map_or(0, |position| position.shares);
+    if locked == 0 || shares <= held.saturating_sub(locked) {
+        return Ok(vault.withdraw_fee_bps);
+    }
+
+    if vault.early_exit_penalty_bps == 0 {
+        return err!(UrbaniumError::MinHoldPeriodActive);
+    }
+
+    vault
+        .withdraw_fee_bps
+        .checked_add(vault.early_exit_penalty_bps)
+        .ok_or_else(|| error!(UrbaniumError::MathOverflow))
+}
+
+/// Entry or exit fee owed on top of a net `amount`: `ceil(amount * bps / BPS)`.
+pub fn fee_on_raw(amount: u64, fee_bps: u16) -> Result<u64> {
+    mul_div_up(amount, u128::from(fee_bps), BPS_DENOMINATOR)
+}
+
+/// Entry or exit fee contained in a gross `amount` that already includes it:
+/// `ceil(amount * bps / (BPS + bps))`.
+pub fn fee_on_total(amount: u64, fee_bps: u16) -> Result<u64> {
+    mul_div_up(
+        amount,
+        u128::from(fee_bps),
+        BPS_DENOMINATOR + u128::from(fee_bps),
+    )
+}
+
+fn mul_div_up(value: u64, numerator: u128, denominator: u128) -> Result<u64> {
+    let product = u128::from(value)
+        .checked_mul(numerator)
+        .ok_or_else(|| error!(UrbaniumError::MathOverflow))?;
+
+    u64::try_from(product.div_ceil(denominator)).map_err(|_| error!(UrbaniumError::MathOverflow))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Position holding `held` shares, `locked` of them until `locked_until`.
+    fn position(held: u64, locked: u64, locked_until: i64) -> UserPosition {
+        let mut position = UserPosition::zeroed();
+        position.shares = held;
+        position.locked_shares = locked;
+        position.locked_until = locked_until;
+        position
+    }
+
+    #[test]
+    fn hold_requires_position() {
+        let mut vault = Vault::zeroed();
+        assert_eq!(locked_shares(&vault, None, 0).unwrap(), 0);
+
+        vault.min_hold_seconds = 60;
+        assert_eq!(
+            locked_shares(&vault, None, 0).unwrap_err(),
+            error!(UrbaniumError::MissingUserPosition)
+        );
+        assert_eq!(
+            exit_fee_bps(&vault, None, 1, 0).unwrap_err(),
+            error!(UrbaniumError::MissingUserPosition)
+        );
+    }
+
+    #[test]
+    fn hold_covers_locked_shares_until_expiry() {
+        let mut vault = Vault::zeroed();
+        vault.min_hold_seconds = 60;
+        vault.withdraw_fee_bps = 25;
+        let position = position(100, 40, 1_060);
+
+        assert_eq!(locked_shares(&vault, Some(&position), 1_059).unwrap(), 40);
+        assert_eq!(locked_shares(&vault, Some(&position), 1_060).unwrap(), 0);
+        assert_eq!(
+            exit_fee_bps(&vault, Some(&position), 60, 1_000).unwrap(),
+            25
+        );
+        assert_eq!(
+            exit_fee_bps(&vault, Some(&position), 61, 1_000).unwrap_err(),
+            error!(UrbaniumError::MinHoldPeriodActive)
+        );
+
+        vault.early_exit_penalty_bps = 100;
+        assert_eq!(
+            exit_fee_bps(&vault, Some(&position), 61, 1_000).unwrap(),
+            125
+        );
+        assert_eq!(
+            exit_fee_bps(&vault, Some(&position), 100, 1_060).unwrap(),
+            25
+        );
+    }
+
+    #[test]
+    fn old_shares_do_not_unlock_a_large_deposit() {
+        const DAY: i64 = 86_400;
+        let mut vault = Vault::zeroed();
+        vault.min_hold_seconds = DAY as u64;
+        let mut position = position(0, 0, 0);
+
+        position
+            .record_hold(1_000, vault.min_hold_seconds, 0, 1)
+            .unwrap();
+        position.shares += 1_000;
+
+        let now = 365 * DAY;
+        position
+            .record_hold(100_000, vault.min_hold_seconds, now, 2)
+            .unwrap();
+        position.shares += 100_000;
+
+        assert_eq!(
+            exit_fee_bps(&vault, Some(&position), 1_000, now).unwrap(),
+            0
+        );
+        assert_eq!(
+            exit_fee_bps(&vault, Some(&position), 101_000, now).unwrap_err(),
+            error!(UrbaniumError::MinHoldPeriodActive)
+        );
+        assert_eq!(
+            exit_fee_bps(&vault, Some(&position), 101_000, now + DAY).unwrap(),
+            0
+        );
+    }
+
+    /// Vault holding `assets` and `shares` including the virtual offset
+    /// (`decimals_offset = 0`).
+    fn funded_vault(assets: u64, shares: u64) -> Vault {
+        let mut vault = Vault::zeroed();
+        vault.total_assets = assets - 1;
+        vault.total_shares = shares - 1;
+        vault.high_water_mark = PRICE_SCALE;
+        vault
+    }
+
+    #[test]
+    fn accrue_on_empty_vault_sets_high_water_mark() {
+        let mut vault = Vault::zeroed();
+        vault.management_fee_bps = 500;
+        vault.performance_fee_bps = 5_000;
+        vault.total_assets = 99; // donated before the first deposit
+
+        assert_eq!(accrue_fees(&mut vault, 1_000).unwrap().fee_shares, 0);
+        assert_eq!(vault.total_shares, 0);
+        assert_eq!(vault.high_water_mark, 100 * PRICE_SCALE);
+        assert_eq!(vault.last_fee_accrual, 1_000);
+    }
+
+    #[test]
+    fn management_fee_caps_elapsed_time_at_one_year() {
+        let year = SECONDS_PER_YEAR as i64;
+        let mut one_year = funded_vault(1_000_000, 1_000_000);
+        one_year.management_fee_bps = 100;
+        let mut ten_years = one_year.clone();
+
+        // 1% of 1_000_000 assets, priced against the 990_000 left.
+        assert_eq!(
+            accrue_fees(&mut one_year, year).unwrap(),
+            FeeAccrual {
+                fee_shares: 10_101,
+                management_fee: 10_000,
+                performance_fee: 0,
+            }
+        );
+        assert_eq!(
+            accrue_fees(&mut ten_years, 10 * year).unwrap().fee_shares,
+            10_101
+        );
+        assert_eq!(ten_years.last_fee_accrual, 10 * year);
+
+        // Nothing more accrues without elapsed time.
+        assert_eq!(
+            accrue_fees(&mut ten_years, 10 * year).unwrap().fee_shares,
+            0
+        );
+    }
+
+    #[test]
+    fn no_performance_fee_below_high_water_mark() {
+        let mut vault = funded_vault(1_000_000, 1_000_000);
+        vault.performance_fee_bps = 1_000;
+        vault.high_water_mark = 2 * PRICE_SCALE;
+
+        assert_eq!(accrue_fees(&mut vault, 0).unwrap().fee_shares, 0);
+        assert_eq!(vault.total_shares, 999_999);
+        assert_eq!(vault.high_water_mark, 2 * PRICE_SCALE);
+    }
+
+    #[test]
+    fn performance_fee_raises_high_water_mark() {
+        let mut vault = funded_vault(2_000_000, 1_000_000);
+        vault.performance_fee_bps = 1_000;
+
+        // 10% of the 1_000_000 assets gained, priced against the 1_900_000
+        // left.
+        assert_eq!(
+            accrue_fees(&mut vault, 0).unwrap(),
+            FeeAccrual {
+                fee_shares: 52_631,
+                management_fee: 0,
+                performance_fee: 100_000,
+            }
+        );
+        assert_eq!(vault.total_shares, 999_999 + 52_631);
+        assert_eq!(vault.high_water_mark, share_price(&vault).unwrap());
+        assert!(vault.high_water_mark > 19 * PRICE_SCALE / 10);
+        assert!(vault.high_water_mark < 2 * PRICE_SCALE);
+
+        // The gain is only charged once.
+        assert_eq!(accrue_fees(&mut vault, 0).unwrap().fee_shares, 0);
+    }
+
+    #[test]
+    fn credit_fee_shares_adds_zero_cost_shares() {
+        let mut fee_position = UserPosition::zeroed();
+        fee_position.shares = 100;
+        fee_position.avg_entry_price = 2 * PRICE_SCALE;
+
+        credit_fee_shares(&mut fee_position, 100).unwrap();
+
+        assert_eq!(fee_position.shares, 200);
+        assert_eq!(fee_position.avg_entry_price, PRICE_SCALE);
+    }
+}
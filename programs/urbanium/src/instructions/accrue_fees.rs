@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::errors::UrbaniumError;
+use crate::fees::accrue_and_credit_fees;
+use crate::pda;
+use crate::state::{UserPosition, Vault};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct AccrueFees<'info> {
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [pda::VAULT_SEED, mint.key().as_ref(), vault.creator.as_ref()],
+        bump = vault.bump,
+        has_one = mint,
+        constraint = vault.version == Vault::VERSION @ UrbaniumError::UnsupportedVaultVersion
+    )]
+    pub vault: Account<'info, Vault>,
+
+    #[account(
+        mut,
+        seeds = [pda::USER_POSITION_SEED, vault.key().as_ref(), vault.fee_recipient.as_ref()],
+        bump = fee_position.bump
+    )]
+    pub fee_position: Account<'info, UserPosition>,
+}
+
+/// Permissionless crank: accrues pending fees without any other state change.
+pub fn handler(ctx: Context<AccrueFees>) -> Result<()> {
+    accrue_and_credit_fees(
+        &mut ctx.accounts.vault,
+        &mut ctx.accounts.fee_position,
+        None,
+        &ctx.accounts.event_authority,
+        ctx.bumps.event_authority,
+        Clock::get()?.unix_timestamp,
+    )
+}
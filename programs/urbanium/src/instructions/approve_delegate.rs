@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::errors::UrbaniumError;
+use crate::events::DelegateApproved;
+use crate::pda;
+use crate::state::{UserPosition, Vault};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ManageDelegate<'info> {
+    pub owner: Signer<'info>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [pda::VAULT_SEED, mint.key().as_ref(), vault.creator.as_ref()],
+        bump = vault.bump,
+        has_one = mint,
+        constraint = vault.version == Vault::VERSION @ UrbaniumError::UnsupportedVaultVersion
+    )]
+    pub vault: Account<'info, Vault>,
+
+    #[account(
+        mut,
+        seeds = [pda::USER_POSITION_SEED, vault.key().as_ref(), owner.key().as_ref()],
+        bump = user_position.bump,
+        constraint = user_position.vault == vault.key() @ UrbaniumError::InvalidUserPositionPda,
+        constraint = user_position.owner == owner.key() @ UrbaniumError::InvalidUserPositionPda,
+    )]
+    pub user_position: Account<'info, UserPosition>,
+}
+
+/// Lets `delegate` withdraw up to `shares` from the position, paid to the
+/// owner. Replaces any previous delegate and allowance.
+pub fn handler(ctx: Context<ManageDelegate>, delegate: Pubkey, shares: u64) -> Result<()> {
+    if delegate == Pubkey::default() || delegate == ctx.accounts.owner.key() {
+        return err!(UrbaniumError::InvalidDelegate);
+    }
+
+    let user_position = &mut ctx.accounts.user_position;
+    user_position.delegate = delegate;
+    user_position.delegated_shares = shares;
+
+    let event = DelegateApproved {
+        vault: ctx.accounts.vault.key(),
+        owner: ctx.accounts.owner.key(),
+        delegate,
+        shares,
+    };
+    emit_cpi!(event);
+
+    Ok(())
+}
@@ -0,0 +1,159 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+use crate::errors::UrbaniumError;
+use crate::events::WithdrawClaimed;
+use crate::instructions::sync_total_assets::summed_balance;
+use crate::instructions::withdraw::Payout;
+use crate::pda;
+use crate::state::{Vault, WithdrawRequest};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimWithdraw<'info> {
+    /// CHECK: Request owner; receives the request rent. Bound by `has_one`
+    /// on `withdraw_request`.
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [pda::VAULT_SEED, mint.key().as_ref(), vault.creator.as_ref()],
+        bump = vault.bump,
+        has_one = mint,
+        constraint = vault.version == Vault::VERSION @ UrbaniumError::UnsupportedVaultVersion
+    )]
+    pub vault: Account<'info, Vault>,
+
+    /// CHECK: PDA authority for vault-controlled token accounts.
+    #[account(
+        seeds = [pda::VAULT_AUTHORITY_SEED, vault.key().as_ref()],
+        bump = vault.authority_bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// Must be the request at the head of the vault queue.
+    #[account(
+        mut,
+        close = owner,
+        seeds = [
+            pda::WITHDRAW_REQUEST_SEED,
+            vault.key().as_ref(),
+            &withdraw_request.id.to_le_bytes()
+        ],
+        bump = withdraw_request.bump,
+        has_one = vault,
+        has_one = owner,
+        constraint = withdraw_request.id == vault.withdraw_queue_head @ UrbaniumError::WithdrawRequestNotNext
+    )]
+    pub withdraw_request: Account<'info, WithdrawRequest>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = owner
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [pda::TOKEN_ACCOUNT_SEED, vault_authority.key().as_ref(), &[pda::PRIMARY_TOKEN_ACCOUNT_ROLE]],
+        bump = vault.vault_token_account_bump,
+        address = vault.vault_token_account @ UrbaniumError::InvalidVaultTokenAccount
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [pda::TOKEN_ACCOUNT_SEED, vault_authority.key().as_ref(), &[pda::YIELD_A_TOKEN_ACCOUNT_ROLE]],
+        bump = vault.yield_token_account_a_bump,
+        address = vault.yield_token_account_a @ UrbaniumError::InvalidYieldTokenAccount
+    )]
+    pub yield_token_account_a: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [pda::TOKEN_ACCOUNT_SEED, vault_authority.key().as_ref(), &[pda::YIELD_B_TOKEN_ACCOUNT_ROLE]],
+        bump = vault.yield_token_account_b_bump,
+        address = vault.yield_token_account_b @ UrbaniumError::InvalidYieldTokenAccount
+    )]
+    pub yield_token_account_b: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Permissionless: pays the request at the head of the queue to its owner's
+/// ATA once the vault token accounts hold enough liquidity, then advances
+/// the queue.
+pub fn handler(ctx: Context<ClaimWithdraw>) -> Result<()> {
+    if ctx.accounts.token_program.key() != anchor_spl::token::ID {
+        return err!(UrbaniumError::InvalidTokenProgram);
+    }
+
+    let assets = ctx.accounts.withdraw_request.assets;
+
+    let liquidity = summed_balance(
+        &ctx.accounts.vault_token_account,
+        &ctx.accounts.yield_token_account_a,
+        &ctx.accounts.yield_token_account_b,
+    )?;
+
+    if liquidity < assets {
+        return err!(UrbaniumError::InsufficientLiquidity);
+    }
+
+    let vault_key = ctx.accounts.vault.key();
+    let auth_seeds: &[&[u8]] = &[
+        pda::VAULT_AUTHORITY_SEED,
+        vault_key.as_ref(),
+        &[ctx.accounts.vault.authority_bump],
+    ];
+
+    let sources = Payout {
+        token_program: ctx.accounts.token_program.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        authority: ctx.accounts.vault_authority.to_account_info(),
+        to: ctx.accounts.user_token_account.to_account_info(),
+        decimals: ctx.accounts.mint.decimals,
+        auth_seeds,
+    }
+    .pay(
+        [
+            &ctx.accounts.vault_token_account,
+            &ctx.accounts.yield_token_account_a,
+            &ctx.accounts.yield_token_account_b,
+        ],
+        assets,
+    )?;
+
+    let vault = &mut ctx.accounts.vault;
+
+    vault.queued_assets = vault
+        .queued_assets
+        .checked_sub(assets)
+        .ok_or_else(|| error!(UrbaniumError::MathOverflow))?;
+
+    vault.withdraw_queue_head = vault
+        .withdraw_queue_head
+        .checked_add(1)
+        .ok_or_else(|| error!(UrbaniumError::MathOverflow))?;
+
+    vault
+        .stats
+        .record_withdraw(assets, Clock::get()?.unix_timestamp)?;
+
+    let event = WithdrawClaimed {
+        vault: vault.key(),
+        owner: ctx.accounts.owner.key(),
+        id: ctx.accounts.withdraw_request.id,
+        assets,
+        from_vault_token_account: sources[0],
+        from_yield_token_account_a: sources[1],
+        from_yield_token_account_b: sources[2],
+    };
+    emit_cpi!(event);
+
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::errors::UrbaniumError;
+use crate::events::PositionClosed;
+use crate::pda;
+use crate::state::{UserPosition, Vault};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClosePosition<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [pda::VAULT_SEED, mint.key().as_ref(), vault.creator.as_ref()],
+        bump = vault.bump,
+        has_one = mint,
+        constraint = vault.fee_recipient != owner.key() @ UrbaniumError::FeePositionNotClosable,
+        constraint = vault.version == Vault::VERSION @ UrbaniumError::UnsupportedVaultVersion
+    )]
+    pub vault: Account<'info, Vault>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [pda::USER_POSITION_SEED, vault.key().as_ref(), owner.key().as_ref()],
+        bump = user_position.bump,
+        constraint = user_position.vault == vault.key() @ UrbaniumError::InvalidUserPositionPda,
+        constraint = user_position.owner == owner.key() @ UrbaniumError::InvalidUserPositionPda,
+        constraint = user_position.shares == 0 @ UrbaniumError::PositionNotEmpty,
+    )]
+    pub user_position: Account<'info, UserPosition>,
+}
+
+/// Closes an empty `UserPosition` and refunds its rent to the owner. A later
+/// deposit recreates the account from scratch.
+pub fn handler(ctx: Context<ClosePosition>) -> Result<()> {
+    ctx.accounts.vault.stats.position_closed();
+
+    let event = PositionClosed {
+        vault: ctx.accounts.vault.key(),
+        owner: ctx.accounts.owner.key(),
+    };
+    emit_cpi!(event);
+
+    Ok(())
+}
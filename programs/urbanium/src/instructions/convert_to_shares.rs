@@ -0,0 +1,10 @@
+use anchor_lang::prelude::*;
+
+use crate::conversion::{self, Rounding};
+use crate::instructions::preview_deposit::{accrued, Preview};
+
+/// Shares worth `assets` at the current exchange rate, rounded down, before
+/// any entry or exit fee.
+pub fn handler(ctx: Context<Preview>, assets: u64) -> Result<u64> {
+    conversion::convert_to_shares(&accrued(&ctx.accounts.vault)?, assets, Rounding::Down)
+}
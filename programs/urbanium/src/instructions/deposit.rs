@@ -1,23 +1,35 @@
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
-use anchor_spl::token::{transfer_checked, Mint, Token, TokenAccount, TransferChecked};
+use anchor_spl::token::{
+    mint_to, transfer_checked, Mint, MintTo, Token, TokenAccount, TransferChecked,
+};
 
+use crate::conversion::{convert_to_assets, quote_deposit, Rounding};
 use crate::errors::UrbaniumError;
+use crate::events::Deposited;
+use crate::fees::accrue_and_credit_fees;
 use crate::pda;
 use crate::state::{UserPosition, Vault};
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct Deposit<'info> {
+    /// Pays the assets and any position rent.
     #[account(mut)]
     pub depositor: Signer<'info>,
 
+    /// CHECK: Owner of the credited position or share tokens. Must equal
+    /// `depositor` except in `deposit_for`.
+    pub beneficiary: UncheckedAccount<'info>,
+
     pub mint: Account<'info, Mint>,
 
     #[account(
         mut,
-        seeds = [pda::VAULT_SEED, mint.key().as_ref()],
+        seeds = [pda::VAULT_SEED, mint.key().as_ref(), vault.creator.as_ref()],
         bump = vault.bump,
-        has_one = mint
+        has_one = mint,
+        constraint = vault.version == Vault::VERSION @ UrbaniumError::UnsupportedVaultVersion
     )]
     pub vault: Account<'info, Vault>,
 
@@ -28,14 +40,26 @@ pub struct Deposit<'info> {
     )]
     pub vault_authority: UncheckedAccount<'info>,
 
+    /// Credited with accrued fee shares. Declared before `user_position` so
+    /// that when both are the same account, the `user_position` write-back
+    /// lands last; fees are then credited through `user_position`.
+    #[account(
+        mut,
+        seeds = [pda::USER_POSITION_SEED, vault.key().as_ref(), vault.fee_recipient.as_ref()],
+        bump = fee_position.bump
+    )]
+    pub fee_position: Account<'info, UserPosition>,
+
+    /// Credited with the shares. Required unless the vault is tokenized, and
+    /// rejected when it is, so share-token deposits never open a position.
     #[account(
         init_if_needed,
         payer = depositor,
         space = 8 + UserPosition::LEN,
-        seeds = [pda::USER_POSITION_SEED, vault.key().as_ref(), depositor.key().as_ref()],
+        seeds = [pda::USER_POSITION_SEED, vault.key().as_ref(), beneficiary.key().as_ref()],
         bump
     )]
-    pub user_position: Account<'info, UserPosition>,
+    pub user_position: Option<Account<'info, UserPosition>>,
 
     #[account(
         mut,
@@ -44,92 +68,262 @@ pub struct Deposit<'info> {
     )]
     pub user_token_account: Account<'info, TokenAccount>,
 
-    #[account(mut, address = vault.vault_token_account)]
+    #[account(
+        mut,
+        seeds = [pda::TOKEN_ACCOUNT_SEED, vault_authority.key().as_ref(), &[pda::PRIMARY_TOKEN_ACCOUNT_ROLE]],
+        bump = vault.vault_token_account_bump,
+        address = vault.vault_token_account @ UrbaniumError::InvalidVaultTokenAccount
+    )]
     pub vault_token_account: Account<'info, TokenAccount>,
 
-    #[account(mut, address = vault.yield_token_account_a)]
-    pub yield_token_account_a: Account<'info, TokenAccount>,
+    /// Required when the vault is tokenized; shares are minted here instead
+    /// of being credited to `user_position`.
+    #[account(mut, address = vault.share_mint @ UrbaniumError::InvalidShareMint)]
+    pub share_mint: Option<Account<'info, Mint>>,
 
-    #[account(mut, address = vault.yield_token_account_b)]
-    pub yield_token_account_b: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = user_share_account.mint == vault.share_mint @ UrbaniumError::InvalidShareMint,
+        constraint = user_share_account.owner == beneficiary.key() @ UrbaniumError::InvalidShareAccountOwner
+    )]
+    pub user_share_account: Option<Account<'info, TokenAccount>>,
 
     pub system_program: Program<'info, System>,
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
-pub fn handler(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+pub fn handler(ctx: Context<Deposit>, amount: u64, min_shares_out: u64) -> Result<()> {
+    require_self_beneficiary(ctx.accounts)?;
+
+    let event = deposit_assets(
+        ctx.accounts,
+        ctx.bumps.event_authority,
+        amount,
+        min_shares_out,
+    )?;
+    emit_cpi!(event);
+
+    Ok(())
+}
+
+/// Converts `amount` at the current rate and settles the deposit for the
+/// beneficiary. Shared by `deposit` and `deposit_for`.
+pub(crate) fn deposit_assets(
+    accounts: &mut Deposit,
+    event_authority_bump: u8,
+    amount: u64,
+    min_shares_out: u64,
+) -> Result<Deposited> {
     if amount == 0 {
         return err!(UrbaniumError::ZeroAmount);
     }
 
-    if ctx.accounts.token_program.key() != anchor_spl::token::ID {
+    if accounts.token_program.key() != anchor_spl::token::ID {
         return err!(UrbaniumError::InvalidTokenProgram);
     }
 
-    let decimals = ctx.accounts.mint.decimals;
-
-    let total_assets: u128 = u128::from(ctx.accounts.vault_token_account.amount)
-        .checked_add(u128::from(ctx.accounts.yield_token_account_a.amount))
-        .ok_or_else(|| error!(UrbaniumError::MathOverflow))?
-        .checked_add(u128::from(ctx.accounts.yield_token_account_b.amount))
-        .ok_or_else(|| error!(UrbaniumError::MathOverflow))?;
+    let pre_totals = (accounts.vault.total_assets, accounts.vault.total_shares);
 
-    let total_shares: u128 = u128::from(ctx.accounts.vault.total_shares);
-    let amount_u128: u128 = u128::from(amount);
+    accrue_deposit_fees(accounts, event_authority_bump)?;
 
-    let shares_to_mint: u64 = if total_shares == 0 || total_assets == 0 {
-        amount
-    } else {
-        let s = amount_u128
-            .checked_mul(total_shares)
-            .ok_or_else(|| error!(UrbaniumError::MathOverflow))?
-            .checked_div(total_assets)
-            .ok_or_else(|| error!(UrbaniumError::MathOverflow))?;
-        u64::try_from(s).map_err(|_| error!(UrbaniumError::MathOverflow))?
-    };
+    let quote = quote_deposit(&accounts.vault, amount)?;
+    let shares_to_mint = quote.amount;
 
     if shares_to_mint == 0 {
-        return err!(UrbaniumError::MathOverflow);
+        return err!(UrbaniumError::ZeroSharesMinted);
+    }
+
+    if shares_to_mint < min_shares_out {
+        return err!(UrbaniumError::SharesBelowMinimum);
+    }
+
+    settle_deposit(accounts, amount, shares_to_mint)?;
+
+    Ok(deposited_event(
+        accounts,
+        amount,
+        shares_to_mint,
+        quote.fee,
+        pre_totals,
+    ))
+}
+
+/// Builds the `Deposited` event once the deposit is settled. `pre_totals` is
+/// `(total_assets, total_shares)` before fee accrual.
+pub(crate) fn deposited_event(
+    accounts: &Deposit,
+    assets: u64,
+    shares: u64,
+    fee: u64,
+    pre_totals: (u64, u64),
+) -> Deposited {
+    Deposited {
+        vault: accounts.vault.key(),
+        depositor: accounts.depositor.key(),
+        owner: accounts.beneficiary.key(),
+        assets,
+        shares,
+        fee,
+        tokenized: accounts.vault.is_tokenized(),
+        pre_total_assets: pre_totals.0,
+        pre_total_shares: pre_totals.1,
+        post_total_assets: accounts.vault.total_assets,
+        post_total_shares: accounts.vault.total_shares,
+    }
+}
+
+/// Accrues pending fees so the deposit is priced after them.
+pub(crate) fn accrue_deposit_fees(accounts: &mut Deposit, event_authority_bump: u8) -> Result<()> {
+    accrue_and_credit_fees(
+        &mut accounts.vault,
+        &mut accounts.fee_position,
+        accounts.user_position.as_mut(),
+        &accounts.event_authority,
+        event_authority_bump,
+        Clock::get()?.unix_timestamp,
+    )
+}
+
+/// `deposit` and `mint_shares` always credit the signer.
+pub(crate) fn require_self_beneficiary(accounts: &Deposit) -> Result<()> {
+    if accounts.beneficiary.key() != accounts.depositor.key() {
+        return err!(UrbaniumError::InvalidBeneficiary);
+    }
+
+    Ok(())
+}
+
+/// Pulls `assets` from the depositor and credits `shares` to the beneficiary's
+/// position, or mints them as share tokens when the vault is tokenized.
+/// Shared by `deposit`, `deposit_for` and `mint_shares` once the conversion is
+/// settled.
+pub(crate) fn settle_deposit(accounts: &mut Deposit, assets: u64, shares: u64) -> Result<()> {
+    match (accounts.vault.is_tokenized(), &accounts.user_position) {
+        (true, Some(_)) => return err!(UrbaniumError::UserPositionWithShareMint),
+        (false, None) => return err!(UrbaniumError::MissingUserPosition),
+        _ => {}
+    }
+
+    if assets > vault_headroom(&accounts.vault) {
+        return err!(UrbaniumError::VaultDepositCapExceeded);
+    }
+
+    let held_shares = accounts
+        .user_position
+        .as_ref()
+        .map_or(0, |user_position| user_position.shares);
+    if assets > user_headroom(&accounts.vault, held_shares)? {
+        return err!(UrbaniumError::UserDepositCapExceeded);
     }
 
     transfer_checked(
         CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
+            accounts.token_program.to_account_info(),
             TransferChecked {
-                from: ctx.accounts.user_token_account.to_account_info(),
-                mint: ctx.accounts.mint.to_account_info(),
-                to: ctx.accounts.vault_token_account.to_account_info(),
-                authority: ctx.accounts.depositor.to_account_info(),
+                from: accounts.user_token_account.to_account_info(),
+                mint: accounts.mint.to_account_info(),
+                to: accounts.vault_token_account.to_account_info(),
+                authority: accounts.depositor.to_account_info(),
             },
         ),
-        amount,
-        decimals,
+        assets,
+        accounts.mint.decimals,
     )?;
 
-    let user_position = &mut ctx.accounts.user_position;
-    if user_position.shares == 0 {
-        let (_expected, bump) = pda::user_position_pda(
-            &ctx.accounts.vault.key(),
-            &ctx.accounts.depositor.key(),
-            &crate::ID,
-        );
-        user_position.bump = bump;
-        user_position.vault = ctx.accounts.vault.key();
-        user_position.owner = ctx.accounts.depositor.key();
+    let clock = Clock::get()?;
+
+    if accounts.vault.is_tokenized() {
+        let (Some(share_mint), Some(user_share_account)) =
+            (&accounts.share_mint, &accounts.user_share_account)
+        else {
+            return err!(UrbaniumError::MissingShareAccount);
+        };
+
+        let vault_key = accounts.vault.key();
+        let auth_seeds: &[&[u8]] = &[
+            pda::VAULT_AUTHORITY_SEED,
+            vault_key.as_ref(),
+            &[accounts.vault.authority_bump],
+        ];
+
+        mint_to(
+            CpiContext::new_with_signer(
+                accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: share_mint.to_account_info(),
+                    to: user_share_account.to_account_info(),
+                    authority: accounts.vault_authority.to_account_info(),
+                },
+                &[auth_seeds],
+            ),
+            shares,
+        )?;
+    } else if let Some(user_position) = &mut accounts.user_position {
+        // Fresh account: first deposit, or the first one after `close_position`.
+        if user_position.vault == Pubkey::default() {
+            let (_expected, bump) = pda::user_position_pda(
+                &accounts.vault.key(),
+                &accounts.beneficiary.key(),
+                &crate::ID,
+            );
+            user_position.bump = bump;
+            user_position.vault = accounts.vault.key();
+            user_position.owner = accounts.beneficiary.key();
+            accounts.vault.stats.position_opened()?;
+        }
+
+        user_position.record_hold(
+            shares,
+            accounts.vault.min_hold_seconds,
+            clock.unix_timestamp,
+            clock.slot,
+        )?;
+        user_position.record_deposit(shares, assets)?;
+        user_position.shares = user_position
+            .shares
+            .checked_add(shares)
+            .ok_or_else(|| error!(UrbaniumError::MathOverflow))?;
     }
 
-    user_position.shares = user_position
-        .shares
-        .checked_add(shares_to_mint)
+    accounts.vault.total_assets = accounts
+        .vault
+        .total_assets
+        .checked_add(assets)
         .ok_or_else(|| error!(UrbaniumError::MathOverflow))?;
 
-    ctx.accounts.vault.total_shares = ctx
-        .accounts
+    accounts.vault.total_shares = accounts
         .vault
         .total_shares
-        .checked_add(shares_to_mint)
+        .checked_add(shares)
         .ok_or_else(|| error!(UrbaniumError::MathOverflow))?;
 
+    accounts
+        .vault
+        .stats
+        .record_deposit(assets, clock.unix_timestamp)?;
+
     Ok(())
 }
+
+/// Assets the vault still accepts under `max_total_assets`.
+pub(crate) fn vault_headroom(vault: &Vault) -> u64 {
+    if vault.max_total_assets == 0 {
+        return u64::MAX;
+    }
+
+    vault.max_total_assets.saturating_sub(vault.total_assets)
+}
+
+/// Assets a position holding `held_shares` may still add under
+/// `max_assets_per_user`, valuing the held shares at the current rate. Only
+/// vaults without a share mint have a per-user cap.
+pub(crate) fn user_headroom(vault: &Vault, held_shares: u64) -> Result<u64> {
+    if vault.max_assets_per_user == 0 {
+        return Ok(u64::MAX);
+    }
+
+    let held_assets = convert_to_assets(vault, held_shares, Rounding::Down)?;
+
+    Ok(vault.max_assets_per_user.saturating_sub(held_assets))
+}
@@ -0,0 +1,17 @@
+use anchor_lang::prelude::*;
+
+use crate::instructions::deposit::{deposit_assets, Deposit};
+
+/// Deposits the signer's tokens into the vault and credits the resulting
+/// shares to `beneficiary`. The signer also pays the position rent.
+pub fn handler(ctx: Context<Deposit>, amount: u64, min_shares_out: u64) -> Result<()> {
+    let event = deposit_assets(
+        ctx.accounts,
+        ctx.bumps.event_authority,
+        amount,
+        min_shares_out,
+    )?;
+    emit_cpi!(event);
+
+    Ok(())
+}
@@ -0,0 +1,91 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{transfer_checked, Mint, Token, TokenAccount, TransferChecked};
+
+use crate::errors::UrbaniumError;
+use crate::events::Donated;
+use crate::pda;
+use crate::state::Vault;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct Donate<'info> {
+    pub donor: Signer<'info>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [pda::VAULT_SEED, mint.key().as_ref(), vault.creator.as_ref()],
+        bump = vault.bump,
+        has_one = mint,
+        constraint = vault.version == Vault::VERSION @ UrbaniumError::UnsupportedVaultVersion
+    )]
+    pub vault: Account<'info, Vault>,
+
+    /// CHECK: PDA authority for vault-controlled token accounts.
+    #[account(
+        seeds = [pda::VAULT_AUTHORITY_SEED, vault.key().as_ref()],
+        bump = vault.authority_bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = donor
+    )]
+    pub donor_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [pda::TOKEN_ACCOUNT_SEED, vault_authority.key().as_ref(), &[pda::PRIMARY_TOKEN_ACCOUNT_ROLE]],
+        bump = vault.vault_token_account_bump,
+        address = vault.vault_token_account @ UrbaniumError::InvalidVaultTokenAccount
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+pub fn handler(ctx: Context<Donate>, amount: u64) -> Result<()> {
+    if amount == 0 {
+        return err!(UrbaniumError::ZeroAmount);
+    }
+
+    if ctx.accounts.token_program.key() != anchor_spl::token::ID {
+        return err!(UrbaniumError::InvalidTokenProgram);
+    }
+
+    transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.donor_token_account.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.vault_token_account.to_account_info(),
+                authority: ctx.accounts.donor.to_account_info(),
+            },
+        ),
+        amount,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    let pre_total_assets = ctx.accounts.vault.total_assets;
+
+    ctx.accounts.vault.total_assets = pre_total_assets
+        .checked_add(amount)
+        .ok_or_else(|| error!(UrbaniumError::MathOverflow))?;
+
+    let event = Donated {
+        vault: ctx.accounts.vault.key(),
+        donor: ctx.accounts.donor.key(),
+        amount,
+        pre_total_assets,
+        post_total_assets: ctx.accounts.vault.total_assets,
+    };
+    emit_cpi!(event);
+
+    Ok(())
+}
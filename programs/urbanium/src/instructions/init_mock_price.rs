@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+
+use crate::state::MockPrice;
+
+#[derive(Accounts)]
+pub struct InitMockPrice<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(init, payer = authority, space = 8 + MockPrice::LEN)]
+    pub mock_price: Account<'info, MockPrice>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Creates a mock price account owned by this program and publishes the
+/// first price at the current time, with the EMA starting at that price.
+/// Use this program's id as the vault's `oracle_program`.
+pub fn handler(ctx: Context<InitMockPrice>, price: i64, conf: u64, expo: i32) -> Result<()> {
+    let mock_price = &mut ctx.accounts.mock_price;
+    mock_price.authority = ctx.accounts.authority.key();
+    mock_price.price = price;
+    mock_price.conf = conf;
+    mock_price.expo = expo;
+    mock_price.publish_time = Clock::get()?.unix_timestamp;
+    mock_price.ema_price = price;
+    mock_price.ema_conf = conf;
+
+    Ok(())
+}
@@ -1,11 +1,15 @@
 use anchor_lang::prelude::*;
-use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token::{Mint, Token, TokenAccount};
 
 use crate::errors::UrbaniumError;
-use crate::oracle::{enforce_confidence_bps, read_pyth_price};
+use crate::events::VaultInitialized;
+use crate::fees::share_price;
+use crate::oracle::{
+    aggregate_prices, enforce_confidence_bps, read_feed_price, read_oracle_price,
+    OracleAggregation, OracleFeed, OracleKind, PriceSource,
+};
 use crate::pda;
-use crate::state::Vault;
+use crate::state::{RouteDestination, UserPosition, Vault, VaultStats};
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct InitializeVaultArgs {
@@ -13,12 +17,41 @@ pub struct InitializeVaultArgs {
     pub oracle_feed: Pubkey,
     pub max_staleness_seconds: u64,
     pub max_confidence_bps: u16,
-    pub route_threshold_price: i64,
+    /// In `oracle::PRICE_DECIMALS` decimals, whatever the feed exponent.
+    pub route_threshold_price: i128,
+    pub decimals_offset: u8,
+    pub fee_recipient: Pubkey,
+    pub management_fee_bps: u16,
+    pub performance_fee_bps: u16,
+    pub max_total_assets: u64,
+    pub max_assets_per_user: u64,
+    pub deposit_fee_bps: u16,
+    pub withdraw_fee_bps: u16,
+    pub min_hold_seconds: u64,
+    pub early_exit_penalty_bps: u16,
+    pub oracle_kind: OracleKind,
+    pub price_source: PriceSource,
+    /// At most `Vault::MAX_SECONDARY_ORACLES`; their accounts follow the
+    /// named accounts in the same order.
+    pub secondary_oracles: Vec<OracleFeed>,
+    pub oracle_quorum: u8,
+    pub oracle_aggregation: OracleAggregation,
+    pub max_oracle_divergence_bps: u16,
+    pub conservative_routing: bool,
+    /// Hysteresis band; must contain `route_threshold_price`. Set both to
+    /// the threshold to disable it.
+    pub route_lower_price: i128,
+    pub route_upper_price: i128,
+    /// Price feed id `oracle_feed` must carry when it is a Pyth pull
+    /// account; ignored by other kinds.
+    pub oracle_feed_id: [u8; 32],
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 #[instruction(args: InitializeVaultArgs)]
 pub struct InitializeVault<'info> {
+    /// Vault creator: seeds the vault PDA with `mint` and pays all rent.
     #[account(mut)]
     pub payer: Signer<'info>,
 
@@ -28,7 +61,7 @@ pub struct InitializeVault<'info> {
         init,
         payer = payer,
         space = 8 + Vault::LEN,
-        seeds = [pda::VAULT_SEED, mint.key().as_ref()],
+        seeds = [pda::VAULT_SEED, mint.key().as_ref(), payer.key().as_ref()],
         bump
     )]
     pub vault: Account<'info, Vault>,
@@ -43,34 +76,59 @@ pub struct InitializeVault<'info> {
     #[account(
         init,
         payer = payer,
-        associated_token::mint = mint,
-        associated_token::authority = vault_authority
+        seeds = [pda::TOKEN_ACCOUNT_SEED, vault_authority.key().as_ref(), &[pda::PRIMARY_TOKEN_ACCOUNT_ROLE]],
+        bump,
+        token::mint = mint,
+        token::authority = vault_authority
     )]
     pub vault_token_account: Account<'info, TokenAccount>,
 
     #[account(
         init,
         payer = payer,
-        associated_token::mint = mint,
-        associated_token::authority = vault_authority
+        seeds = [pda::TOKEN_ACCOUNT_SEED, vault_authority.key().as_ref(), &[pda::YIELD_A_TOKEN_ACCOUNT_ROLE]],
+        bump,
+        token::mint = mint,
+        token::authority = vault_authority
     )]
     pub yield_token_account_a: Account<'info, TokenAccount>,
 
     #[account(
         init,
         payer = payer,
-        associated_token::mint = mint,
-        associated_token::authority = vault_authority
+        seeds = [pda::TOKEN_ACCOUNT_SEED, vault_authority.key().as_ref(), &[pda::YIELD_B_TOKEN_ACCOUNT_ROLE]],
+        bump,
+        token::mint = mint,
+        token::authority = vault_authority
     )]
     pub yield_token_account_b: Account<'info, TokenAccount>,
 
+    /// Optional SPL mint for tokenized shares; omit to keep shares in `UserPosition` only.
+    #[account(
+        init,
+        payer = payer,
+        seeds = [pda::SHARE_MINT_SEED, vault.key().as_ref()],
+        bump,
+        mint::decimals = mint.decimals.saturating_add(args.decimals_offset),
+        mint::authority = vault_authority
+    )]
+    pub share_mint: Option<Account<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + UserPosition::LEN,
+        seeds = [pda::USER_POSITION_SEED, vault.key().as_ref(), args.fee_recipient.as_ref()],
+        bump
+    )]
+    pub fee_position: Account<'info, UserPosition>,
+
     /// CHECK: Oracle feed is validated by owner + deserialization.
     #[account(address = args.oracle_feed)]
     pub oracle_feed: UncheckedAccount<'info>,
 
     pub system_program: Program<'info, System>,
     pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
 pub fn handler(ctx: Context<InitializeVault>, args: InitializeVaultArgs) -> Result<()> {
@@ -78,43 +136,181 @@ pub fn handler(ctx: Context<InitializeVault>, args: InitializeVaultArgs) -> Resu
         return err!(UrbaniumError::InvalidTokenProgram);
     }
 
-    let (expected_vault, vault_bump) = pda::vault_pda(&ctx.accounts.mint.key(), &crate::ID);
+    if args.decimals_offset > Vault::MAX_DECIMALS_OFFSET {
+        return err!(UrbaniumError::InvalidDecimalsOffset);
+    }
+
+    if args.management_fee_bps > Vault::MAX_MANAGEMENT_FEE_BPS
+        || args.performance_fee_bps > Vault::MAX_PERFORMANCE_FEE_BPS
+        || args.deposit_fee_bps > Vault::MAX_DEPOSIT_FEE_BPS
+        || args.withdraw_fee_bps > Vault::MAX_WITHDRAW_FEE_BPS
+        || args.early_exit_penalty_bps > Vault::MAX_EARLY_EXIT_PENALTY_BPS
+    {
+        return err!(UrbaniumError::InvalidFeeBps);
+    }
+
+    if args.secondary_oracles.len() > Vault::MAX_SECONDARY_ORACLES
+        || usize::from(args.oracle_quorum) > args.secondary_oracles.len() + 1
+        || (!args.secondary_oracles.is_empty() && args.oracle_quorum == 0)
+    {
+        return err!(UrbaniumError::InvalidOracleConfig);
+    }
+
+    // Share tokens move freely between wallets, so neither a holding period
+    // nor a per-user cap could follow them.
+    if args.min_hold_seconds > 0 && ctx.accounts.share_mint.is_some() {
+        return err!(UrbaniumError::MinHoldWithShareMint);
+    }
+
+    if args.max_assets_per_user > 0 && ctx.accounts.share_mint.is_some() {
+        return err!(UrbaniumError::UserCapWithShareMint);
+    }
+
+    if args.route_lower_price > args.route_threshold_price
+        || args.route_upper_price < args.route_threshold_price
+    {
+        return err!(UrbaniumError::InvalidRouteBand);
+    }
+
+    let (expected_vault, vault_bump) = pda::vault_pda(
+        &ctx.accounts.mint.key(),
+        &ctx.accounts.payer.key(),
+        &crate::ID,
+    );
     if expected_vault != ctx.accounts.vault.key() {
         return err!(UrbaniumError::InvalidVaultPda);
     }
 
-    let (expected_auth, auth_bump) = pda::vault_authority_pda(&ctx.accounts.vault.key(), &crate::ID);
+    let (expected_auth, auth_bump) =
+        pda::vault_authority_pda(&ctx.accounts.vault.key(), &crate::ID);
     if expected_auth != ctx.accounts.vault_authority.key() {
         return err!(UrbaniumError::InvalidVaultAuthorityPda);
     }
 
-    let oracle_price = read_pyth_price(
-        &args.oracle_program,
+    let primary_oracle = OracleFeed {
+        kind: args.oracle_kind,
+        program: args.oracle_program,
+        feed: args.oracle_feed,
+        feed_id: args.oracle_feed_id,
+    };
+    let oracle_price = read_oracle_price(
+        &primary_oracle,
         &ctx.accounts.oracle_feed.to_account_info(),
         args.max_staleness_seconds,
-    )?;
+    )?
+    .select(args.price_source)?;
 
     enforce_confidence_bps(oracle_price, args.max_confidence_bps)?;
+    let normalized_price = oracle_price.normalize()?;
 
     let vault = &mut ctx.accounts.vault;
     vault.version = Vault::VERSION;
     vault.bump = vault_bump;
     vault.authority_bump = auth_bump;
+    vault.vault_token_account_bump = ctx.bumps.vault_token_account;
+    vault.yield_token_account_a_bump = ctx.bumps.yield_token_account_a;
+    vault.yield_token_account_b_bump = ctx.bumps.yield_token_account_b;
+    vault.share_mint_bump = ctx.bumps.share_mint.unwrap_or_default();
 
     vault.mint = ctx.accounts.mint.key();
+    vault.creator = ctx.accounts.payer.key();
     vault.vault_token_account = ctx.accounts.vault_token_account.key();
     vault.yield_token_account_a = ctx.accounts.yield_token_account_a.key();
     vault.yield_token_account_b = ctx.accounts.yield_token_account_b.key();
+    vault.share_mint = ctx
+        .accounts
+        .share_mint
+        .as_ref()
+        .map(|share_mint| share_mint.key())
+        .unwrap_or_default();
 
     vault.oracle_program = args.oracle_program;
     vault.oracle_feed = args.oracle_feed;
+    vault.oracle_kind = args.oracle_kind;
+    vault.oracle_feed_id = args.oracle_feed_id;
+    vault.price_source = args.price_source;
     vault.oracle_expo = oracle_price.expo;
 
     vault.max_staleness_seconds = args.max_staleness_seconds;
     vault.max_confidence_bps = args.max_confidence_bps;
     vault.route_threshold_price = args.route_threshold_price;
+    vault.conservative_routing = args.conservative_routing;
+    vault.route_lower_price = args.route_lower_price;
+    vault.route_upper_price = args.route_upper_price;
+    vault.last_route_destination = RouteDestination::Unset;
+
+    vault.secondary_oracles[..args.secondary_oracles.len()]
+        .copy_from_slice(&args.secondary_oracles);
+    vault.secondary_oracle_count = args.secondary_oracles.len() as u8;
+    vault.oracle_quorum = args.oracle_quorum;
+    vault.oracle_aggregation = args.oracle_aggregation;
+    vault.max_oracle_divergence_bps = args.max_oracle_divergence_bps;
+
+    // Unlike `route_yield`, every secondary feed must be usable now, so a
+    // misconfigured feed fails here instead of silently never counting.
+    if !args.secondary_oracles.is_empty() {
+        if ctx.remaining_accounts.len() < args.secondary_oracles.len() {
+            return err!(UrbaniumError::InvalidOracleFeed);
+        }
 
+        let mut prices = vec![normalized_price];
+        for (feed, info) in args.secondary_oracles.iter().zip(ctx.remaining_accounts) {
+            if info.key() != feed.feed {
+                return err!(UrbaniumError::InvalidOracleFeed);
+            }
+            prices.push(read_feed_price(vault, feed, info)?);
+        }
+        aggregate_prices(vault, &prices)?;
+    }
+
+    vault.total_assets = 0;
     vault.total_shares = 0;
+    vault.decimals_offset = args.decimals_offset;
+
+    vault.fee_recipient = args.fee_recipient;
+    vault.management_fee_bps = args.management_fee_bps;
+    vault.performance_fee_bps = args.performance_fee_bps;
+    vault.high_water_mark = share_price(vault)?;
+    vault.last_fee_accrual = Clock::get()?.unix_timestamp;
+    vault.deposit_fee_bps = args.deposit_fee_bps;
+    vault.withdraw_fee_bps = args.withdraw_fee_bps;
+
+    vault.max_total_assets = args.max_total_assets;
+    vault.max_assets_per_user = args.max_assets_per_user;
+
+    vault.min_hold_seconds = args.min_hold_seconds;
+    vault.early_exit_penalty_bps = args.early_exit_penalty_bps;
+
+    // Counts the fee position created below.
+    vault.stats = VaultStats {
+        open_positions: 1,
+        ..VaultStats::default()
+    };
+
+    let fee_position = &mut ctx.accounts.fee_position;
+    fee_position.bump = ctx.bumps.fee_position;
+    fee_position.vault = ctx.accounts.vault.key();
+    fee_position.owner = args.fee_recipient;
+
+    let vault = &ctx.accounts.vault;
+    let event = VaultInitialized {
+        vault: vault.key(),
+        mint: vault.mint,
+        creator: vault.creator,
+        share_mint: vault.share_mint,
+        oracle_program: vault.oracle_program,
+        oracle_feed: vault.oracle_feed,
+        oracle_kind: vault.oracle_kind,
+        price_source: vault.price_source,
+        oracle_expo: vault.oracle_expo,
+        route_threshold_price: vault.route_threshold_price,
+        route_lower_price: vault.route_lower_price,
+        route_upper_price: vault.route_upper_price,
+        conservative_routing: vault.conservative_routing,
+        decimals_offset: vault.decimals_offset,
+        fee_recipient: vault.fee_recipient,
+    };
+    emit_cpi!(event);
 
     Ok(())
 }
@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::errors::UrbaniumError;
+use crate::fees::accrue_fees;
+use crate::instructions::deposit::{user_headroom, vault_headroom};
+use crate::pda;
+use crate::state::{UserPosition, Vault};
+
+#[derive(Accounts)]
+#[instruction(owner: Pubkey)]
+pub struct MaxDeposit<'info> {
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [pda::VAULT_SEED, mint.key().as_ref(), vault.creator.as_ref()],
+        bump = vault.bump,
+        has_one = mint,
+        constraint = vault.version == Vault::VERSION @ UrbaniumError::UnsupportedVaultVersion
+    )]
+    pub vault: Account<'info, Vault>,
+
+    /// Omit when `owner` has no position yet.
+    #[account(
+        seeds = [pda::USER_POSITION_SEED, vault.key().as_ref(), owner.as_ref()],
+        bump = user_position.bump,
+        constraint = user_position.vault == vault.key() @ UrbaniumError::InvalidUserPositionPda,
+        constraint = user_position.owner == owner @ UrbaniumError::InvalidUserPositionPda,
+    )]
+    pub user_position: Option<Account<'info, UserPosition>>,
+}
+
+/// Largest `amount` a `deposit` crediting `owner` accepts right now: the
+/// smaller of the vault and per-user cap headroom, `u64::MAX` when uncapped.
+pub fn handler(ctx: Context<MaxDeposit>, owner: Pubkey) -> Result<u64> {
+    let mut vault = (*ctx.accounts.vault).clone();
+    let fee_shares = accrue_fees(&mut vault, Clock::get()?.unix_timestamp)?.fee_shares;
+
+    let mut held_shares = ctx
+        .accounts
+        .user_position
+        .as_ref()
+        .map_or(0, |user_position| user_position.shares);
+
+    if owner == vault.fee_recipient {
+        held_shares = held_shares
+            .checked_add(fee_shares)
+            .ok_or_else(|| error!(UrbaniumError::MathOverflow))?;
+    }
+
+    Ok(core::cmp::min(
+        vault_headroom(&vault),
+        user_headroom(&vault, held_shares)?,
+    ))
+}
@@ -0,0 +1,117 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, TokenAccount};
+
+use crate::conversion::quote_redeem;
+use crate::errors::UrbaniumError;
+use crate::fees::{accrue_fees, exit_fee_bps, locked_shares};
+use crate::instructions::sync_total_assets::summed_balance;
+use crate::pda;
+use crate::state::{UserPosition, Vault};
+
+#[derive(Accounts)]
+#[instruction(owner: Pubkey)]
+pub struct MaxWithdraw<'info> {
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [pda::VAULT_SEED, mint.key().as_ref(), vault.creator.as_ref()],
+        bump = vault.bump,
+        has_one = mint,
+        constraint = vault.version == Vault::VERSION @ UrbaniumError::UnsupportedVaultVersion
+    )]
+    pub vault: Account<'info, Vault>,
+
+    /// CHECK: PDA authority for vault-controlled token accounts.
+    #[account(
+        seeds = [pda::VAULT_AUTHORITY_SEED, vault.key().as_ref()],
+        bump = vault.authority_bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [pda::USER_POSITION_SEED, vault.key().as_ref(), owner.as_ref()],
+        bump = user_position.bump,
+        constraint = user_position.vault == vault.key() @ UrbaniumError::InvalidUserPositionPda,
+        constraint = user_position.owner == owner @ UrbaniumError::InvalidUserPositionPda,
+    )]
+    pub user_position: Option<Account<'info, UserPosition>>,
+
+    #[account(
+        constraint = user_share_account.mint == vault.share_mint @ UrbaniumError::InvalidShareMint,
+        constraint = user_share_account.owner == owner @ UrbaniumError::InvalidShareAccountOwner
+    )]
+    pub user_share_account: Option<Account<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [pda::TOKEN_ACCOUNT_SEED, vault_authority.key().as_ref(), &[pda::PRIMARY_TOKEN_ACCOUNT_ROLE]],
+        bump = vault.vault_token_account_bump,
+        address = vault.vault_token_account @ UrbaniumError::InvalidVaultTokenAccount
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        seeds = [pda::TOKEN_ACCOUNT_SEED, vault_authority.key().as_ref(), &[pda::YIELD_A_TOKEN_ACCOUNT_ROLE]],
+        bump = vault.yield_token_account_a_bump,
+        address = vault.yield_token_account_a @ UrbaniumError::InvalidYieldTokenAccount
+    )]
+    pub yield_token_account_a: Account<'info, TokenAccount>,
+
+    #[account(
+        seeds = [pda::TOKEN_ACCOUNT_SEED, vault_authority.key().as_ref(), &[pda::YIELD_B_TOKEN_ACCOUNT_ROLE]],
+        bump = vault.yield_token_account_b_bump,
+        address = vault.yield_token_account_b @ UrbaniumError::InvalidYieldTokenAccount
+    )]
+    pub yield_token_account_b: Account<'info, TokenAccount>,
+}
+
+/// Largest amount `owner` can take out in a single `withdraw` /
+/// `withdraw_assets` with the same share source: the share token account when
+/// supplied, otherwise the position. Capped by the liquidity held across the
+/// vault token accounts, net of queued withdraw requests. Pending fees are
+/// accrued first, as `withdraw` would. The exit fee and any early-exit
+/// penalty are deducted; shares still in their holding period are left out
+/// when the vault rejects early exits.
+pub fn handler(ctx: Context<MaxWithdraw>, owner: Pubkey) -> Result<u64> {
+    let now = Clock::get()?.unix_timestamp;
+    let mut vault = (*ctx.accounts.vault).clone();
+    let fee_shares = accrue_fees(&mut vault, now)?.fee_shares;
+
+    let shares = match (
+        &ctx.accounts.user_share_account,
+        &ctx.accounts.user_position,
+    ) {
+        (Some(user_share_account), _) => user_share_account.amount,
+        (None, Some(user_position)) if owner == vault.fee_recipient => user_position
+            .shares
+            .checked_add(fee_shares)
+            .ok_or_else(|| error!(UrbaniumError::MathOverflow))?,
+        (None, Some(user_position)) => user_position.shares,
+        (None, None) => 0,
+    };
+
+    let user_position = ctx.accounts.user_position.as_deref();
+    let locked = locked_shares(&vault, user_position, now)?;
+    let (shares, fee_bps) = match locked {
+        0 => (shares, vault.withdraw_fee_bps),
+        _ if vault.early_exit_penalty_bps == 0 => {
+            (shares.saturating_sub(locked), vault.withdraw_fee_bps)
+        }
+        // Taking every share includes the locked ones.
+        _ => (shares, exit_fee_bps(&vault, user_position, shares, now)?),
+    };
+
+    if shares == 0 || vault.total_shares == 0 {
+        return Ok(0);
+    }
+
+    let assets = quote_redeem(&vault, shares, fee_bps)?.amount;
+
+    let liquidity = summed_balance(
+        &ctx.accounts.vault_token_account,
+        &ctx.accounts.yield_token_account_a,
+        &ctx.accounts.yield_token_account_b,
+    )?;
+    let liquidity = liquidity.saturating_sub(vault.queued_assets);
+
+    Ok(core::cmp::min(assets, liquidity))
+}
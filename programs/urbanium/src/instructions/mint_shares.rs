@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+
+use crate::conversion::quote_mint;
+use crate::errors::UrbaniumError;
+use crate::instructions::deposit::{
+    accrue_deposit_fees, deposited_event, require_self_beneficiary, settle_deposit, Deposit,
+};
+
+pub fn handler(ctx: Context<Deposit>, shares: u64, max_assets_in: u64) -> Result<()> {
+    require_self_beneficiary(ctx.accounts)?;
+
+    if shares == 0 {
+        return err!(UrbaniumError::ZeroShares);
+    }
+
+    if ctx.accounts.token_program.key() != anchor_spl::token::ID {
+        return err!(UrbaniumError::InvalidTokenProgram);
+    }
+
+    let pre_totals = (
+        ctx.accounts.vault.total_assets,
+        ctx.accounts.vault.total_shares,
+    );
+
+    accrue_deposit_fees(ctx.accounts, ctx.bumps.event_authority)?;
+
+    let quote = quote_mint(&ctx.accounts.vault, shares)?;
+    let assets_in = quote.amount;
+
+    if assets_in > max_assets_in {
+        return err!(UrbaniumError::AssetsAboveMaximum);
+    }
+
+    settle_deposit(ctx.accounts, assets_in, shares)?;
+
+    let event = deposited_event(ctx.accounts, assets_in, shares, quote.fee, pre_totals);
+    emit_cpi!(event);
+
+    Ok(())
+}
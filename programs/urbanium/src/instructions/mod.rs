@@ -1,9 +1,50 @@
+#![allow(ambiguous_glob_reexports)]
+
+pub mod accrue_fees;
+pub mod approve_delegate;
+pub mod claim_withdraw;
+pub mod close_position;
+pub mod convert_to_assets;
+pub mod convert_to_shares;
 pub mod deposit;
+pub mod deposit_for;
+pub mod donate;
+#[cfg(feature = "test-oracle")]
+pub mod init_mock_price;
 pub mod initialize_vault;
+pub mod max_deposit;
+pub mod max_withdraw;
+pub mod mint_shares;
+pub mod preview_deposit;
+pub mod preview_withdraw;
+pub mod request_withdraw;
+pub mod revoke_delegate;
 pub mod route_yield;
+#[cfg(feature = "test-oracle")]
+pub mod set_mock_price;
+pub mod sync_total_assets;
+pub mod tokenize_position;
+pub mod transfer_shares;
 pub mod withdraw;
+pub mod withdraw_assets;
 
+pub use accrue_fees::*;
+pub use approve_delegate::*;
+pub use claim_withdraw::*;
+pub use close_position::*;
 pub use deposit::*;
+pub use donate::*;
+#[cfg(feature = "test-oracle")]
+pub use init_mock_price::*;
 pub use initialize_vault::*;
+pub use max_deposit::*;
+pub use max_withdraw::*;
+pub use preview_deposit::*;
+pub use request_withdraw::*;
 pub use route_yield::*;
+#[cfg(feature = "test-oracle")]
+pub use set_mock_price::*;
+pub use sync_total_assets::*;
+pub use tokenize_position::*;
+pub use transfer_shares::*;
 pub use withdraw::*;
@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::conversion::{quote_deposit, Quote};
+use crate::errors::UrbaniumError;
+use crate::fees::accrue_fees;
+use crate::pda;
+use crate::state::Vault;
+
+/// Read-only accounts for the conversion views. The views return their result
+/// through return data and never mutate state, so they are meant to be
+/// simulated or invoked via CPI.
+#[derive(Accounts)]
+pub struct Preview<'info> {
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [pda::VAULT_SEED, mint.key().as_ref(), vault.creator.as_ref()],
+        bump = vault.bump,
+        has_one = mint,
+        constraint = vault.version == Vault::VERSION @ UrbaniumError::UnsupportedVaultVersion
+    )]
+    pub vault: Account<'info, Vault>,
+}
+
+/// Shares `deposit(amount)` would mint right now, and the entry fee in assets
+/// it would charge.
+pub fn handler(ctx: Context<Preview>, amount: u64) -> Result<Quote> {
+    quote_deposit(&accrued(&ctx.accounts.vault)?, amount)
+}
+
+/// Copy of `vault` with pending fees accrued, as the next state-changing
+/// instruction would see it.
+pub(crate) fn accrued(vault: &Vault) -> Result<Vault> {
+    let mut vault = vault.clone();
+    accrue_fees(&mut vault, Clock::get()?.unix_timestamp)?;
+
+    Ok(vault)
+}
@@ -0,0 +1,12 @@
+use anchor_lang::prelude::*;
+
+use crate::conversion::{quote_redeem, Quote};
+use crate::instructions::preview_deposit::{accrued, Preview};
+
+/// Assets `withdraw(shares)` would pay out right now, and the exit fee in
+/// assets it would charge. Any early-exit penalty depends on the position and
+/// is not included; see `max_withdraw`.
+pub fn handler(ctx: Context<Preview>, shares: u64) -> Result<Quote> {
+    let vault = accrued(&ctx.accounts.vault)?;
+    quote_redeem(&vault, shares, vault.withdraw_fee_bps)
+}
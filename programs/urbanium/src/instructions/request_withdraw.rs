@@ -0,0 +1,205 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{burn, Burn, Mint, Token, TokenAccount};
+
+use crate::conversion::quote_redeem;
+use crate::errors::UrbaniumError;
+use crate::events::WithdrawRequested;
+use crate::fees::{accrue_and_credit_fees, exit_fee_bps};
+use crate::pda;
+use crate::state::{UserPosition, Vault, WithdrawRequest};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RequestWithdraw<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [pda::VAULT_SEED, mint.key().as_ref(), vault.creator.as_ref()],
+        bump = vault.bump,
+        has_one = mint,
+        constraint = vault.version == Vault::VERSION @ UrbaniumError::UnsupportedVaultVersion
+    )]
+    pub vault: Account<'info, Vault>,
+
+    /// Credited with accrued fee shares; see `Withdraw::fee_position`.
+    #[account(
+        mut,
+        seeds = [pda::USER_POSITION_SEED, vault.key().as_ref(), vault.fee_recipient.as_ref()],
+        bump = fee_position.bump
+    )]
+    pub fee_position: Account<'info, UserPosition>,
+
+    /// Share source for untokenized balances; may be omitted when burning
+    /// share tokens from `user_share_account`.
+    #[account(
+        mut,
+        seeds = [pda::USER_POSITION_SEED, vault.key().as_ref(), owner.key().as_ref()],
+        bump = user_position.bump,
+        constraint = user_position.vault == vault.key() @ UrbaniumError::InvalidUserPositionPda,
+        constraint = user_position.owner == owner.key() @ UrbaniumError::InvalidUserPositionPda,
+    )]
+    pub user_position: Option<Account<'info, UserPosition>>,
+
+    /// Takes the next id at the tail of the vault queue.
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + WithdrawRequest::LEN,
+        seeds = [
+            pda::WITHDRAW_REQUEST_SEED,
+            vault.key().as_ref(),
+            &vault.withdraw_queue_tail.to_le_bytes()
+        ],
+        bump
+    )]
+    pub withdraw_request: Account<'info, WithdrawRequest>,
+
+    #[account(mut, address = vault.share_mint @ UrbaniumError::InvalidShareMint)]
+    pub share_mint: Option<Account<'info, Mint>>,
+
+    /// When present, shares are burned from this account instead of
+    /// `user_position`.
+    #[account(
+        mut,
+        constraint = user_share_account.mint == vault.share_mint @ UrbaniumError::InvalidShareMint,
+        constraint = user_share_account.owner == owner.key() @ UrbaniumError::InvalidShareAccountOwner
+    )]
+    pub user_share_account: Option<Account<'info, TokenAccount>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+}
+
+/// Burns `shares` now and queues their value, priced like `withdraw`, for a
+/// later `claim_withdraw`. The queued assets leave `total_assets`, so the
+/// request no longer earns or loses yield.
+pub fn handler(ctx: Context<RequestWithdraw>, shares: u64, min_amount_out: u64) -> Result<()> {
+    if shares == 0 {
+        return err!(UrbaniumError::ZeroShares);
+    }
+
+    if ctx.accounts.token_program.key() != anchor_spl::token::ID {
+        return err!(UrbaniumError::InvalidTokenProgram);
+    }
+
+    let bump = ctx.bumps.withdraw_request;
+    let accounts = &mut *ctx.accounts;
+    let pre_totals = (accounts.vault.total_assets, accounts.vault.total_shares);
+
+    let now = Clock::get()?.unix_timestamp;
+
+    accrue_and_credit_fees(
+        &mut accounts.vault,
+        &mut accounts.fee_position,
+        accounts.user_position.as_mut(),
+        &accounts.event_authority,
+        ctx.bumps.event_authority,
+        now,
+    )?;
+
+    if accounts.vault.total_shares == 0 {
+        return err!(UrbaniumError::InsufficientLiquidity);
+    }
+
+    let fee_bps = exit_fee_bps(
+        &accounts.vault,
+        accounts.user_position.as_deref(),
+        shares,
+        now,
+    )?;
+    let quote = quote_redeem(&accounts.vault, shares, fee_bps)?;
+    let assets = quote.amount;
+
+    if assets == 0 {
+        return err!(UrbaniumError::ZeroAmount);
+    }
+
+    if assets < min_amount_out {
+        return err!(UrbaniumError::AmountBelowMinimum);
+    }
+
+    match (&accounts.user_share_account, &mut accounts.user_position) {
+        (Some(user_share_account), _) => {
+            if user_share_account.amount < shares {
+                return err!(UrbaniumError::InsufficientShares);
+            }
+
+            let share_mint = accounts
+                .share_mint
+                .as_ref()
+                .ok_or_else(|| error!(UrbaniumError::MissingShareAccount))?;
+
+            burn(
+                CpiContext::new(
+                    accounts.token_program.to_account_info(),
+                    Burn {
+                        mint: share_mint.to_account_info(),
+                        from: user_share_account.to_account_info(),
+                        authority: accounts.owner.to_account_info(),
+                    },
+                ),
+                shares,
+            )?;
+        }
+        (None, Some(user_position)) => {
+            user_position.record_withdraw(shares, assets)?;
+            user_position.shares = user_position
+                .shares
+                .checked_sub(shares)
+                .ok_or_else(|| error!(UrbaniumError::InsufficientShares))?;
+        }
+        (None, None) => return err!(UrbaniumError::MissingShareAccount),
+    }
+
+    let vault = &mut accounts.vault;
+    let id = vault.withdraw_queue_tail;
+
+    vault.total_shares = vault
+        .total_shares
+        .checked_sub(shares)
+        .ok_or_else(|| error!(UrbaniumError::MathOverflow))?;
+
+    vault.total_assets = vault
+        .total_assets
+        .checked_sub(assets)
+        .ok_or_else(|| error!(UrbaniumError::MathOverflow))?;
+
+    vault.queued_assets = vault
+        .queued_assets
+        .checked_add(assets)
+        .ok_or_else(|| error!(UrbaniumError::MathOverflow))?;
+
+    vault.withdraw_queue_tail = id
+        .checked_add(1)
+        .ok_or_else(|| error!(UrbaniumError::MathOverflow))?;
+
+    let withdraw_request = &mut accounts.withdraw_request;
+    withdraw_request.bump = bump;
+    withdraw_request.vault = vault.key();
+    withdraw_request.owner = accounts.owner.key();
+    withdraw_request.id = id;
+    withdraw_request.shares = shares;
+    withdraw_request.assets = assets;
+    withdraw_request.requested_at = now;
+
+    let event = WithdrawRequested {
+        vault: accounts.vault.key(),
+        owner: accounts.owner.key(),
+        id,
+        shares,
+        assets,
+        fee: quote.fee,
+        tokenized: accounts.user_share_account.is_some(),
+        pre_total_assets: pre_totals.0,
+        pre_total_shares: pre_totals.1,
+        post_total_assets: accounts.vault.total_assets,
+        post_total_shares: accounts.vault.total_shares,
+    };
+    emit_cpi!(event);
+
+    Ok(())
+}
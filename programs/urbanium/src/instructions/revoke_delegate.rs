@@ -0,0 +1,20 @@
+use anchor_lang::prelude::*;
+
+use crate::events::DelegateRevoked;
+use crate::instructions::approve_delegate::ManageDelegate;
+
+pub fn handler(ctx: Context<ManageDelegate>) -> Result<()> {
+    let user_position = &mut ctx.accounts.user_position;
+    let delegate = user_position.delegate;
+    user_position.delegate = Pubkey::default();
+    user_position.delegated_shares = 0;
+
+    let event = DelegateRevoked {
+        vault: ctx.accounts.vault.key(),
+        owner: ctx.accounts.owner.key(),
+        delegate,
+    };
+    emit_cpi!(event);
+
+    Ok(())
+}
@@ -2,10 +2,13 @@ use anchor_lang::prelude::*;
 use anchor_spl::token::{transfer_checked, Mint, Token, TokenAccount, TransferChecked};
 
 use crate::errors::UrbaniumError;
-use crate::oracle::{enforce_confidence_bps, read_pyth_price};
+use crate::events::YieldRouted;
+use crate::fees::accrue_and_credit_fees;
+use crate::oracle::read_vault_price;
 use crate::pda;
-use crate::state::Vault;
+use crate::state::{RouteDestination, UserPosition, Vault};
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct RouteYield<'info> {
     pub executor: Signer<'info>,
@@ -14,9 +17,10 @@ pub struct RouteYield<'info> {
 
     #[account(
         mut,
-        seeds = [pda::VAULT_SEED, mint.key().as_ref()],
+        seeds = [pda::VAULT_SEED, mint.key().as_ref(), vault.creator.as_ref()],
         bump = vault.bump,
-        has_one = mint
+        has_one = mint,
+        constraint = vault.version == Vault::VERSION @ UrbaniumError::UnsupportedVaultVersion
     )]
     pub vault: Account<'info, Vault>,
 
@@ -27,13 +31,35 @@ pub struct RouteYield<'info> {
     )]
     pub vault_authority: UncheckedAccount<'info>,
 
-    #[account(mut, address = vault.vault_token_account)]
+    #[account(
+        mut,
+        seeds = [pda::USER_POSITION_SEED, vault.key().as_ref(), vault.fee_recipient.as_ref()],
+        bump = fee_position.bump
+    )]
+    pub fee_position: Account<'info, UserPosition>,
+
+    #[account(
+        mut,
+        seeds = [pda::TOKEN_ACCOUNT_SEED, vault_authority.key().as_ref(), &[pda::PRIMARY_TOKEN_ACCOUNT_ROLE]],
+        bump = vault.vault_token_account_bump,
+        address = vault.vault_token_account @ UrbaniumError::InvalidVaultTokenAccount
+    )]
     pub vault_token_account: Account<'info, TokenAccount>,
 
-    #[account(mut, address = vault.yield_token_account_a)]
+    #[account(
+        mut,
+        seeds = [pda::TOKEN_ACCOUNT_SEED, vault_authority.key().as_ref(), &[pda::YIELD_A_TOKEN_ACCOUNT_ROLE]],
+        bump = vault.yield_token_account_a_bump,
+        address = vault.yield_token_account_a @ UrbaniumError::InvalidYieldTokenAccount
+    )]
     pub yield_token_account_a: Account<'info, TokenAccount>,
 
-    #[account(mut, address = vault.yield_token_account_b)]
+    #[account(
+        mut,
+        seeds = [pda::TOKEN_ACCOUNT_SEED, vault_authority.key().as_ref(), &[pda::YIELD_B_TOKEN_ACCOUNT_ROLE]],
+        bump = vault.yield_token_account_b_bump,
+        address = vault.yield_token_account_b @ UrbaniumError::InvalidYieldTokenAccount
+    )]
     pub yield_token_account_b: Account<'info, TokenAccount>,
 
     /// CHECK: Validated by owner and deserialization.
@@ -52,19 +78,30 @@ pub fn handler(ctx: Context<RouteYield>, amount: u64) -> Result<()> {
         return err!(UrbaniumError::InvalidTokenProgram);
     }
 
-    let oracle_price = read_pyth_price(
-        &ctx.accounts.vault.oracle_program,
-        &ctx.accounts.oracle_feed.to_account_info(),
-        ctx.accounts.vault.max_staleness_seconds,
-    )?;
+    let pre_totals = (
+        ctx.accounts.vault.total_assets,
+        ctx.accounts.vault.total_shares,
+    );
 
-    enforce_confidence_bps(oracle_price, ctx.accounts.vault.max_confidence_bps)?;
+    let now = Clock::get()?.unix_timestamp;
 
-    if oracle_price.expo != ctx.accounts.vault.oracle_expo {
-        return err!(UrbaniumError::OracleExponentMismatch);
-    }
+    accrue_and_credit_fees(
+        &mut ctx.accounts.vault,
+        &mut ctx.accounts.fee_position,
+        None,
+        &ctx.accounts.event_authority,
+        ctx.bumps.event_authority,
+        now,
+    )?;
+
+    let oracle_price = read_vault_price(
+        &ctx.accounts.vault,
+        &ctx.accounts.oracle_feed.to_account_info(),
+        ctx.remaining_accounts,
+    )?;
 
-    let destination_is_a = oracle_price.price >= ctx.accounts.vault.route_threshold_price;
+    let route = ctx.accounts.vault.route_destination(&oracle_price)?;
+    let destination_is_a = route == RouteDestination::YieldA;
     let destination = if destination_is_a {
         &ctx.accounts.yield_token_account_a
     } else {
@@ -76,9 +113,10 @@ pub fn handler(ctx: Context<RouteYield>, amount: u64) -> Result<()> {
     }
 
     let decimals = ctx.accounts.mint.decimals;
+    let vault_key = ctx.accounts.vault.key();
     let auth_seeds: &[&[u8]] = &[
         pda::VAULT_AUTHORITY_SEED,
-        ctx.accounts.vault.key().as_ref(),
+        vault_key.as_ref(),
         &[ctx.accounts.vault.authority_bump],
     ];
 
@@ -97,5 +135,28 @@ pub fn handler(ctx: Context<RouteYield>, amount: u64) -> Result<()> {
         decimals,
     )?;
 
+    ctx.accounts
+        .vault
+        .stats
+        .record_route(destination_is_a, amount, now)?;
+    ctx.accounts.vault.last_route_destination = route;
+
+    let vault = &ctx.accounts.vault;
+    let event = YieldRouted {
+        vault: vault.key(),
+        amount,
+        destination: destination.key(),
+        price_source: vault.price_source,
+        oracle_price: oracle_price.price,
+        oracle_conf: oracle_price.conf,
+        oracle_publish_time: oracle_price.publish_time,
+        route_threshold_price: vault.route_threshold_price,
+        pre_total_assets: pre_totals.0,
+        pre_total_shares: pre_totals.1,
+        post_total_assets: vault.total_assets,
+        post_total_shares: vault.total_shares,
+    };
+    emit_cpi!(event);
+
     Ok(())
 }
@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+
+use crate::state::MockPrice;
+
+#[derive(Accounts)]
+pub struct SetMockPrice<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(mut, has_one = authority)]
+    pub mock_price: Account<'info, MockPrice>,
+}
+
+/// Overwrites the mock price. `publish_time` is taken as given so tests can
+/// publish stale or future-dated prices.
+pub fn handler(
+    ctx: Context<SetMockPrice>,
+    price: i64,
+    conf: u64,
+    expo: i32,
+    publish_time: i64,
+    ema_price: i64,
+    ema_conf: u64,
+) -> Result<()> {
+    let mock_price = &mut ctx.accounts.mock_price;
+    mock_price.price = price;
+    mock_price.conf = conf;
+    mock_price.expo = expo;
+    mock_price.publish_time = publish_time;
+    mock_price.ema_price = ema_price;
+    mock_price.ema_conf = ema_conf;
+
+    Ok(())
+}
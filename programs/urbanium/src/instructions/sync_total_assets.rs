@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, TokenAccount};
+
+use crate::errors::UrbaniumError;
+use crate::events::TotalAssetsSynced;
+use crate::pda;
+use crate::state::Vault;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SyncTotalAssets<'info> {
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [pda::VAULT_SEED, mint.key().as_ref(), vault.creator.as_ref()],
+        bump = vault.bump,
+        has_one = mint,
+        constraint = vault.version == Vault::VERSION @ UrbaniumError::UnsupportedVaultVersion
+    )]
+    pub vault: Account<'info, Vault>,
+
+    /// CHECK: PDA authority for vault-controlled token accounts.
+    #[account(
+        seeds = [pda::VAULT_AUTHORITY_SEED, vault.key().as_ref()],
+        bump = vault.authority_bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [pda::TOKEN_ACCOUNT_SEED, vault_authority.key().as_ref(), &[pda::PRIMARY_TOKEN_ACCOUNT_ROLE]],
+        bump = vault.vault_token_account_bump,
+        address = vault.vault_token_account @ UrbaniumError::InvalidVaultTokenAccount
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        seeds = [pda::TOKEN_ACCOUNT_SEED, vault_authority.key().as_ref(), &[pda::YIELD_A_TOKEN_ACCOUNT_ROLE]],
+        bump = vault.yield_token_account_a_bump,
+        address = vault.yield_token_account_a @ UrbaniumError::InvalidYieldTokenAccount
+    )]
+    pub yield_token_account_a: Account<'info, TokenAccount>,
+
+    #[account(
+        seeds = [pda::TOKEN_ACCOUNT_SEED, vault_authority.key().as_ref(), &[pda::YIELD_B_TOKEN_ACCOUNT_ROLE]],
+        bump = vault.yield_token_account_b_bump,
+        address = vault.yield_token_account_b @ UrbaniumError::InvalidYieldTokenAccount
+    )]
+    pub yield_token_account_b: Account<'info, TokenAccount>,
+}
+
+/// Folds tokens that reached the vault token accounts outside of program
+/// instructions into `Vault.total_assets`. The accounted total only ever
+/// grows here; balances below it are left untouched.
+pub fn handler(ctx: Context<SyncTotalAssets>) -> Result<()> {
+    let balance = summed_balance(
+        &ctx.accounts.vault_token_account,
+        &ctx.accounts.yield_token_account_a,
+        &ctx.accounts.yield_token_account_b,
+    )?;
+
+    // Tokens owed to queued withdraw requests no longer back shares.
+    let balance = balance.saturating_sub(ctx.accounts.vault.queued_assets);
+
+    let pre_total_assets = ctx.accounts.vault.total_assets;
+
+    if balance > pre_total_assets {
+        ctx.accounts.vault.total_assets = balance;
+    }
+
+    let event = TotalAssetsSynced {
+        vault: ctx.accounts.vault.key(),
+        balance,
+        pre_total_assets,
+        post_total_assets: ctx.accounts.vault.total_assets,
+    };
+    emit_cpi!(event);
+
+    Ok(())
+}
+
+/// Combined balance of the primary and both yield token accounts.
+pub(crate) fn summed_balance(
+    primary: &TokenAccount,
+    yield_a: &TokenAccount,
+    yield_b: &TokenAccount,
+) -> Result<u64> {
+    let balance: u128 = u128::from(primary.amount)
+        .checked_add(u128::from(yield_a.amount))
+        .ok_or_else(|| error!(UrbaniumError::MathOverflow))?
+        .checked_add(u128::from(yield_b.amount))
+        .ok_or_else(|| error!(UrbaniumError::MathOverflow))?;
+
+    u64::try_from(balance).map_err(|_| error!(UrbaniumError::MathOverflow))
+}
@@ -0,0 +1,96 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{mint_to, Mint, MintTo, Token, TokenAccount};
+
+use crate::errors::UrbaniumError;
+use crate::events::PositionTokenized;
+use crate::pda;
+use crate::state::{UserPosition, Vault};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct TokenizePosition<'info> {
+    pub owner: Signer<'info>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [pda::VAULT_SEED, mint.key().as_ref(), vault.creator.as_ref()],
+        bump = vault.bump,
+        has_one = mint,
+        has_one = share_mint @ UrbaniumError::InvalidShareMint,
+        constraint = vault.version == Vault::VERSION @ UrbaniumError::UnsupportedVaultVersion
+    )]
+    pub vault: Account<'info, Vault>,
+
+    /// CHECK: PDA authority for vault-controlled token accounts.
+    #[account(
+        seeds = [pda::VAULT_AUTHORITY_SEED, vault.key().as_ref()],
+        bump = vault.authority_bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [pda::USER_POSITION_SEED, vault.key().as_ref(), owner.key().as_ref()],
+        bump = user_position.bump,
+        constraint = user_position.vault == vault.key() @ UrbaniumError::InvalidUserPositionPda,
+        constraint = user_position.owner == owner.key() @ UrbaniumError::InvalidUserPositionPda,
+    )]
+    pub user_position: Account<'info, UserPosition>,
+
+    #[account(mut)]
+    pub share_mint: Account<'info, Mint>,
+
+    #[account(mut, token::mint = share_mint)]
+    pub user_share_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Moves the full `UserPosition.shares` balance into share tokens. The vault
+/// share supply is unchanged; only the form in which the shares are held.
+pub fn handler(ctx: Context<TokenizePosition>) -> Result<()> {
+    if ctx.accounts.token_program.key() != anchor_spl::token::ID {
+        return err!(UrbaniumError::InvalidTokenProgram);
+    }
+
+    if !ctx.accounts.vault.is_tokenized() {
+        return err!(UrbaniumError::ShareMintNotEnabled);
+    }
+
+    let shares = ctx.accounts.user_position.shares;
+    if shares == 0 {
+        return err!(UrbaniumError::ZeroShares);
+    }
+
+    let vault_key = ctx.accounts.vault.key();
+    let auth_seeds: &[&[u8]] = &[
+        pda::VAULT_AUTHORITY_SEED,
+        vault_key.as_ref(),
+        &[ctx.accounts.vault.authority_bump],
+    ];
+
+    mint_to(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            MintTo {
+                mint: ctx.accounts.share_mint.to_account_info(),
+                to: ctx.accounts.user_share_account.to_account_info(),
+                authority: ctx.accounts.vault_authority.to_account_info(),
+            },
+            &[auth_seeds],
+        ),
+        shares,
+    )?;
+
+    ctx.accounts.user_position.record_tokenized();
+
+    let event = PositionTokenized {
+        vault: ctx.accounts.vault.key(),
+        owner: ctx.accounts.owner.key(),
+        shares,
+    };
+    emit_cpi!(event);
+
+    Ok(())
+}
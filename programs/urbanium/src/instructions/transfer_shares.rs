@@ -0,0 +1,112 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::conversion::{convert_to_assets, Rounding};
+use crate::errors::UrbaniumError;
+use crate::events::SharesTransferred;
+use crate::instructions::deposit::user_headroom;
+use crate::pda;
+use crate::state::{UserPosition, Vault};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct TransferShares<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: Any wallet may receive shares; only its key seeds the position.
+    #[account(constraint = recipient.key() != owner.key() @ UrbaniumError::SelfShareTransfer)]
+    pub recipient: UncheckedAccount<'info>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [pda::VAULT_SEED, mint.key().as_ref(), vault.creator.as_ref()],
+        bump = vault.bump,
+        has_one = mint,
+        constraint = vault.version == Vault::VERSION @ UrbaniumError::UnsupportedVaultVersion
+    )]
+    pub vault: Account<'info, Vault>,
+
+    #[account(
+        mut,
+        seeds = [pda::USER_POSITION_SEED, vault.key().as_ref(), owner.key().as_ref()],
+        bump = user_position.bump,
+        constraint = user_position.vault == vault.key() @ UrbaniumError::InvalidUserPositionPda,
+        constraint = user_position.owner == owner.key() @ UrbaniumError::InvalidUserPositionPda,
+    )]
+    pub user_position: Account<'info, UserPosition>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + UserPosition::LEN,
+        seeds = [pda::USER_POSITION_SEED, vault.key().as_ref(), recipient.key().as_ref()],
+        bump
+    )]
+    pub recipient_position: Account<'info, UserPosition>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Moves `amount` shares between two positions of the same vault. The vault
+/// share supply and exchange rate are unchanged.
+pub fn handler(ctx: Context<TransferShares>, amount: u64) -> Result<()> {
+    if amount == 0 {
+        return err!(UrbaniumError::ZeroShares);
+    }
+
+    let user_position = &mut ctx.accounts.user_position;
+    if user_position.shares < amount {
+        return err!(UrbaniumError::InsufficientShares);
+    }
+
+    user_position.shares = user_position
+        .shares
+        .checked_sub(amount)
+        .ok_or_else(|| error!(UrbaniumError::MathOverflow))?;
+
+    // The recipient takes over the sender's cost basis for these shares.
+    // Like a deposit, they start a new holding period in its position.
+    let entry_price = user_position.avg_entry_price;
+
+    // Incoming shares count against the recipient's per-user cap like a
+    // deposit of their current value.
+    let recipient_position = &mut ctx.accounts.recipient_position;
+    let value = convert_to_assets(&ctx.accounts.vault, amount, Rounding::Down)?;
+    if value > user_headroom(&ctx.accounts.vault, recipient_position.shares)? {
+        return err!(UrbaniumError::UserDepositCapExceeded);
+    }
+
+    if recipient_position.vault == Pubkey::default() {
+        recipient_position.bump = ctx.bumps.recipient_position;
+        recipient_position.vault = ctx.accounts.vault.key();
+        recipient_position.owner = ctx.accounts.recipient.key();
+        ctx.accounts.vault.stats.position_opened()?;
+    }
+
+    let clock = Clock::get()?;
+    recipient_position.record_hold(
+        amount,
+        ctx.accounts.vault.min_hold_seconds,
+        clock.unix_timestamp,
+        clock.slot,
+    )?;
+    recipient_position.record_acquired(amount, entry_price)?;
+    recipient_position.shares = recipient_position
+        .shares
+        .checked_add(amount)
+        .ok_or_else(|| error!(UrbaniumError::MathOverflow))?;
+
+    let event = SharesTransferred {
+        vault: ctx.accounts.vault.key(),
+        owner: ctx.accounts.owner.key(),
+        recipient: ctx.accounts.recipient.key(),
+        shares: amount,
+        entry_price,
+    };
+    emit_cpi!(event);
+
+    Ok(())
+}
@@ -1,23 +1,36 @@
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
-use anchor_spl::token::{transfer_checked, Mint, Token, TokenAccount, TransferChecked};
+use anchor_spl::token::{burn, transfer_checked, Burn, Mint, Token, TokenAccount, TransferChecked};
 
+use crate::conversion::quote_redeem;
 use crate::errors::UrbaniumError;
+use crate::events::{PositionClosed, Withdrawn};
+use crate::fees::{accrue_and_credit_fees, exit_fee_bps};
+use crate::instructions::sync_total_assets::summed_balance;
 use crate::pda;
 use crate::state::{UserPosition, Vault};
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct Withdraw<'info> {
+    /// The position owner, or its delegate when withdrawing from
+    /// `user_position`.
     #[account(mut)]
     pub withdrawer: Signer<'info>,
 
+    /// CHECK: Receives the assets and any refunded rent; bound to
+    /// `user_position`, `user_token_account` and `user_share_account`.
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+
     pub mint: Account<'info, Mint>,
 
     #[account(
         mut,
-        seeds = [pda::VAULT_SEED, mint.key().as_ref()],
+        seeds = [pda::VAULT_SEED, mint.key().as_ref(), vault.creator.as_ref()],
         bump = vault.bump,
-        has_one = mint
+        has_one = mint,
+        constraint = vault.version == Vault::VERSION @ UrbaniumError::UnsupportedVaultVersion
     )]
     pub vault: Account<'info, Vault>,
 
@@ -28,37 +41,82 @@ pub struct Withdraw<'info> {
     )]
     pub vault_authority: UncheckedAccount<'info>,
 
+    /// Credited with accrued fee shares. Declared before `user_position` so
+    /// that when both are the same account, the `user_position` write-back
+    /// lands last; fees are then credited through `user_position`.
+    #[account(
+        mut,
+        seeds = [pda::USER_POSITION_SEED, vault.key().as_ref(), vault.fee_recipient.as_ref()],
+        bump = fee_position.bump
+    )]
+    pub fee_position: Account<'info, UserPosition>,
+
+    /// Share source for untokenized balances; may be omitted when burning
+    /// share tokens from `user_share_account`.
     #[account(
         mut,
-        seeds = [pda::USER_POSITION_SEED, vault.key().as_ref(), withdrawer.key().as_ref()],
+        seeds = [pda::USER_POSITION_SEED, vault.key().as_ref(), owner.key().as_ref()],
         bump = user_position.bump,
         constraint = user_position.vault == vault.key() @ UrbaniumError::InvalidUserPositionPda,
-        constraint = user_position.owner == withdrawer.key() @ UrbaniumError::InvalidUserPositionPda,
+        constraint = user_position.owner == owner.key() @ UrbaniumError::InvalidUserPositionPda,
     )]
-    pub user_position: Account<'info, UserPosition>,
+    pub user_position: Option<Account<'info, UserPosition>>,
 
     #[account(
         mut,
         associated_token::mint = mint,
-        associated_token::authority = withdrawer
+        associated_token::authority = owner
     )]
     pub user_token_account: Account<'info, TokenAccount>,
 
-    #[account(mut, address = vault.vault_token_account)]
+    #[account(
+        mut,
+        seeds = [pda::TOKEN_ACCOUNT_SEED, vault_authority.key().as_ref(), &[pda::PRIMARY_TOKEN_ACCOUNT_ROLE]],
+        bump = vault.vault_token_account_bump,
+        address = vault.vault_token_account @ UrbaniumError::InvalidVaultTokenAccount
+    )]
     pub vault_token_account: Account<'info, TokenAccount>,
 
-    #[account(mut, address = vault.yield_token_account_a)]
+    #[account(
+        mut,
+        seeds = [pda::TOKEN_ACCOUNT_SEED, vault_authority.key().as_ref(), &[pda::YIELD_A_TOKEN_ACCOUNT_ROLE]],
+        bump = vault.yield_token_account_a_bump,
+        address = vault.yield_token_account_a @ UrbaniumError::InvalidYieldTokenAccount
+    )]
     pub yield_token_account_a: Account<'info, TokenAccount>,
 
-    #[account(mut, address = vault.yield_token_account_b)]
+    #[account(
+        mut,
+        seeds = [pda::TOKEN_ACCOUNT_SEED, vault_authority.key().as_ref(), &[pda::YIELD_B_TOKEN_ACCOUNT_ROLE]],
+        bump = vault.yield_token_account_b_bump,
+        address = vault.yield_token_account_b @ UrbaniumError::InvalidYieldTokenAccount
+    )]
     pub yield_token_account_b: Account<'info, TokenAccount>,
 
+    #[account(mut, address = vault.share_mint @ UrbaniumError::InvalidShareMint)]
+    pub share_mint: Option<Account<'info, Mint>>,
+
+    /// When present, shares are burned from this account instead of
+    /// `user_position`. A withdrawer other than the owner needs an SPL token
+    /// approval on it.
+    #[account(
+        mut,
+        constraint = user_share_account.mint == vault.share_mint @ UrbaniumError::InvalidShareMint,
+        constraint = user_share_account.owner == owner.key() @ UrbaniumError::InvalidShareAccountOwner
+    )]
+    pub user_share_account: Option<Account<'info, TokenAccount>>,
+
     pub system_program: Program<'info, System>,
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
-pub fn handler(ctx: Context<Withdraw>, shares: u64) -> Result<()> {
+pub fn handler(
+    ctx: Context<Withdraw>,
+    shares: u64,
+    min_amount_out: u64,
+    close_if_empty: bool,
+) -> Result<()> {
     if shares == 0 {
         return err!(UrbaniumError::ZeroShares);
     }
@@ -67,118 +125,308 @@ pub fn handler(ctx: Context<Withdraw>, shares: u64) -> Result<()> {
         return err!(UrbaniumError::InvalidTokenProgram);
     }
 
-    if ctx.accounts.user_position.shares < shares {
+    let pre_totals = (
+        ctx.accounts.vault.total_assets,
+        ctx.accounts.vault.total_shares,
+    );
+
+    accrue_withdraw_fees(ctx.accounts, ctx.bumps.event_authority)?;
+
+    if held_shares(ctx.accounts)? < shares {
         return err!(UrbaniumError::InsufficientShares);
     }
 
-    let total_assets: u128 = u128::from(ctx.accounts.vault_token_account.amount)
-        .checked_add(u128::from(ctx.accounts.yield_token_account_a.amount))
-        .ok_or_else(|| error!(UrbaniumError::MathOverflow))?
-        .checked_add(u128::from(ctx.accounts.yield_token_account_b.amount))
-        .ok_or_else(|| error!(UrbaniumError::MathOverflow))?;
-
-    let total_shares: u128 = u128::from(ctx.accounts.vault.total_shares);
-    if total_shares == 0 {
+    if ctx.accounts.vault.total_shares == 0 {
         return err!(UrbaniumError::InsufficientLiquidity);
     }
 
-    let amount_out_u128: u128 = u128::from(shares)
-        .checked_mul(total_assets)
-        .ok_or_else(|| error!(UrbaniumError::MathOverflow))?
-        .checked_div(total_shares)
-        .ok_or_else(|| error!(UrbaniumError::MathOverflow))?;
+    let fee_bps = position_exit_fee_bps(ctx.accounts, shares)?;
+    let quote = quote_redeem(&ctx.accounts.vault, shares, fee_bps)?;
+    let amount_out = quote.amount;
 
-    let mut remaining: u64 = u64::try_from(amount_out_u128)
-        .map_err(|_| error!(UrbaniumError::MathOverflow))?;
+    if amount_out < min_amount_out {
+        return err!(UrbaniumError::AmountBelowMinimum);
+    }
 
-    let decimals = ctx.accounts.mint.decimals;
+    let sources = settle_withdraw(ctx.accounts, shares, amount_out)?;
+    let event = withdrawn_event(
+        ctx.accounts,
+        shares,
+        amount_out,
+        quote.fee,
+        sources,
+        pre_totals,
+    );
+
+    emit_cpi!(event);
+
+    if close_if_empty && close_if_emptied(ctx.accounts)? {
+        let event = PositionClosed {
+            vault: ctx.accounts.vault.key(),
+            owner: ctx.accounts.owner.key(),
+        };
+        emit_cpi!(event);
+    }
 
-    let auth_seeds: &[&[u8]] = &[
-        pda::VAULT_AUTHORITY_SEED,
-        ctx.accounts.vault.key().as_ref(),
-        &[ctx.accounts.vault.authority_bump],
-    ];
+    Ok(())
+}
 
-    // Deterministic liquidity order: main vault account -> yield A -> yield B.
-    remaining = transfer_up_to(
-        &ctx,
-        &ctx.accounts.vault_token_account,
-        remaining,
-        decimals,
-        auth_seeds,
-    )?;
+/// Closes the position to its owner once it holds no shares, and reports
+/// whether it did. A position that still holds shares, was not supplied, or
+/// collects the vault fees is left as is.
+fn close_if_emptied(accounts: &mut Withdraw) -> Result<bool> {
+    match &accounts.user_position {
+        Some(user_position)
+            if user_position.shares == 0 && user_position.owner != accounts.vault.fee_recipient =>
+        {
+            user_position.close(accounts.owner.to_account_info())?;
+            accounts.vault.stats.position_closed();
+            Ok(true)
+        }
+        _ => Ok(false),
+    }
+}
 
-    remaining = transfer_up_to(
-        &ctx,
-        &ctx.accounts.yield_token_account_a,
-        remaining,
-        decimals,
-        auth_seeds,
+/// Burns `shares` from the share token account (or the position) and pays
+/// `assets` to the user ATA. Returns the amount drawn from each vault token
+/// account, in liquidity order.
+/// Shared by `withdraw` and `withdraw_assets` once the conversion is settled.
+pub(crate) fn settle_withdraw(
+    accounts: &mut Withdraw,
+    shares: u64,
+    assets: u64,
+) -> Result<[u64; 3]> {
+    let liquidity = summed_balance(
+        &accounts.vault_token_account,
+        &accounts.yield_token_account_a,
+        &accounts.yield_token_account_b,
     )?;
 
-    remaining = transfer_up_to(
-        &ctx,
-        &ctx.accounts.yield_token_account_b,
-        remaining,
-        decimals,
+    // Tokens owed to queued withdraw requests are not available here.
+    if liquidity.saturating_sub(accounts.vault.queued_assets) < assets {
+        return err!(UrbaniumError::InsufficientLiquidity);
+    }
+
+    let vault_key = accounts.vault.key();
+    let auth_seeds: &[&[u8]] = &[
+        pda::VAULT_AUTHORITY_SEED,
+        vault_key.as_ref(),
+        &[accounts.vault.authority_bump],
+    ];
+
+    let sources = Payout {
+        token_program: accounts.token_program.to_account_info(),
+        mint: accounts.mint.to_account_info(),
+        authority: accounts.vault_authority.to_account_info(),
+        to: accounts.user_token_account.to_account_info(),
+        decimals: accounts.mint.decimals,
         auth_seeds,
+    }
+    .pay(
+        [
+            &accounts.vault_token_account,
+            &accounts.yield_token_account_a,
+            &accounts.yield_token_account_b,
+        ],
+        assets,
     )?;
 
-    if remaining != 0 {
-        return err!(UrbaniumError::InsufficientLiquidity);
+    match (&accounts.user_share_account, &mut accounts.user_position) {
+        (Some(user_share_account), _) => {
+            let share_mint = accounts
+                .share_mint
+                .as_ref()
+                .ok_or_else(|| error!(UrbaniumError::MissingShareAccount))?;
+
+            burn(
+                CpiContext::new(
+                    accounts.token_program.to_account_info(),
+                    Burn {
+                        mint: share_mint.to_account_info(),
+                        from: user_share_account.to_account_info(),
+                        authority: accounts.withdrawer.to_account_info(),
+                    },
+                ),
+                shares,
+            )?;
+        }
+        (None, Some(user_position)) => {
+            if accounts.withdrawer.key() != user_position.owner {
+                user_position.delegated_shares = user_position
+                    .delegated_shares
+                    .checked_sub(shares)
+                    .ok_or_else(|| error!(UrbaniumError::DelegateAllowanceExceeded))?;
+            }
+
+            user_position.record_withdraw(shares, assets)?;
+            user_position.shares = user_position
+                .shares
+                .checked_sub(shares)
+                .ok_or_else(|| error!(UrbaniumError::MathOverflow))?;
+        }
+        (None, None) => return err!(UrbaniumError::MissingShareAccount),
     }
 
-    ctx.accounts.user_position.shares = ctx
-        .accounts
-        .user_position
-        .shares
+    accounts.vault.total_shares = accounts
+        .vault
+        .total_shares
         .checked_sub(shares)
         .ok_or_else(|| error!(UrbaniumError::MathOverflow))?;
 
-    ctx.accounts.vault.total_shares = ctx
-        .accounts
+    accounts.vault.total_assets = accounts
         .vault
-        .total_shares
-        .checked_sub(shares)
+        .total_assets
+        .checked_sub(assets)
         .ok_or_else(|| error!(UrbaniumError::MathOverflow))?;
 
-    Ok(())
+    accounts
+        .vault
+        .stats
+        .record_withdraw(assets, Clock::get()?.unix_timestamp)?;
+
+    Ok(sources)
 }
 
-fn transfer_up_to<'info>(
-    ctx: &Context<'_, '_, '_, 'info, Withdraw<'info>>,
-    from: &Account<'info, TokenAccount>,
-    remaining: u64,
-    decimals: u8,
-    auth_seeds: &[&[u8]],
-) -> Result<u64> {
-    if remaining == 0 {
-        return Ok(0);
+/// Builds the `Withdrawn` event once the withdrawal is settled. `pre_totals`
+/// is `(total_assets, total_shares)` before fee accrual.
+pub(crate) fn withdrawn_event(
+    accounts: &Withdraw,
+    shares: u64,
+    assets: u64,
+    fee: u64,
+    sources: [u64; 3],
+    pre_totals: (u64, u64),
+) -> Withdrawn {
+    Withdrawn {
+        vault: accounts.vault.key(),
+        withdrawer: accounts.withdrawer.key(),
+        owner: accounts.owner.key(),
+        shares,
+        assets,
+        fee,
+        from_vault_token_account: sources[0],
+        from_yield_token_account_a: sources[1],
+        from_yield_token_account_b: sources[2],
+        pre_total_assets: pre_totals.0,
+        pre_total_shares: pre_totals.1,
+        post_total_assets: accounts.vault.total_assets,
+        post_total_shares: accounts.vault.total_shares,
     }
+}
+
+/// Accrues pending fees so the withdrawal is priced after them.
+pub(crate) fn accrue_withdraw_fees(
+    accounts: &mut Withdraw,
+    event_authority_bump: u8,
+) -> Result<()> {
+    accrue_and_credit_fees(
+        &mut accounts.vault,
+        &mut accounts.fee_position,
+        accounts.user_position.as_mut(),
+        &accounts.event_authority,
+        event_authority_bump,
+        Clock::get()?.unix_timestamp,
+    )
+}
 
-    let available = from.amount;
-    if available == 0 {
-        return Ok(remaining);
+/// Exit fee for this withdrawal, including the early-exit penalty when the
+/// supplied position is still inside the vault's holding period.
+pub(crate) fn position_exit_fee_bps(accounts: &Withdraw, shares: u64) -> Result<u16> {
+    exit_fee_bps(
+        &accounts.vault,
+        accounts.user_position.as_deref(),
+        shares,
+        Clock::get()?.unix_timestamp,
+    )
+}
+
+/// Shares the withdrawer can burn in this instruction: the share token
+/// balance when a share account is supplied, otherwise the position balance,
+/// limited to the remaining allowance when the withdrawer is the delegate.
+pub(crate) fn held_shares(accounts: &Withdraw) -> Result<u64> {
+    match (&accounts.user_share_account, &accounts.user_position) {
+        (Some(user_share_account), _) => Ok(user_share_account.amount),
+        (None, Some(user_position)) => {
+            let withdrawer = accounts.withdrawer.key();
+            if withdrawer == user_position.owner {
+                Ok(user_position.shares)
+            } else if withdrawer == user_position.delegate {
+                Ok(core::cmp::min(
+                    user_position.shares,
+                    user_position.delegated_shares,
+                ))
+            } else {
+                err!(UrbaniumError::UnauthorizedWithdrawer)
+            }
+        }
+        (None, None) => err!(UrbaniumError::MissingShareAccount),
     }
+}
 
-    let to_send = core::cmp::min(available, remaining);
-
-    transfer_checked(
-        CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            TransferChecked {
-                from: from.to_account_info(),
-                mint: ctx.accounts.mint.to_account_info(),
-                to: ctx.accounts.user_token_account.to_account_info(),
-                authority: ctx.accounts.vault_authority.to_account_info(),
-            },
-            &[auth_seeds],
-        ),
-        to_send,
-        decimals,
-    )?;
+/// Signed transfer of vault liquidity to a single destination.
+pub(crate) struct Payout<'a, 'info> {
+    pub token_program: AccountInfo<'info>,
+    pub mint: AccountInfo<'info>,
+    pub authority: AccountInfo<'info>,
+    pub to: AccountInfo<'info>,
+    pub decimals: u8,
+    pub auth_seeds: &'a [&'a [u8]],
+}
+
+impl<'info> Payout<'_, 'info> {
+    /// Pays `assets` draining `sources` in order and returns the amount taken
+    /// from each. The deterministic liquidity order is main vault account ->
+    /// yield A -> yield B.
+    pub fn pay(
+        &self,
+        sources: [&Account<'info, TokenAccount>; 3],
+        assets: u64,
+    ) -> Result<[u64; 3]> {
+        let mut remaining = assets;
+        let mut drawn = [0u64; 3];
+
+        for (from, drawn) in sources.into_iter().zip(drawn.iter_mut()) {
+            let left = self.transfer_up_to(from, remaining)?;
+            *drawn = remaining - left;
+            remaining = left;
+        }
+
+        if remaining != 0 {
+            return err!(UrbaniumError::InsufficientLiquidity);
+        }
+
+        Ok(drawn)
+    }
 
-    Ok(remaining
-        .checked_sub(to_send)
-        .ok_or_else(|| error!(UrbaniumError::MathOverflow))?)
+    fn transfer_up_to(&self, from: &Account<'info, TokenAccount>, remaining: u64) -> Result<u64> {
+        if remaining == 0 {
+            return Ok(0);
+        }
+
+        let available = from.amount;
+        if available == 0 {
+            return Ok(remaining);
+        }
+
+        let to_send = core::cmp::min(available, remaining);
+
+        transfer_checked(
+            CpiContext::new_with_signer(
+                self.token_program.clone(),
+                TransferChecked {
+                    from: from.to_account_info(),
+                    mint: self.mint.clone(),
+                    to: self.to.clone(),
+                    authority: self.authority.clone(),
+                },
+                &[self.auth_seeds],
+            ),
+            to_send,
+            self.decimals,
+        )?;
+
+        remaining
+            .checked_sub(to_send)
+            .ok_or_else(|| error!(UrbaniumError::MathOverflow))
+    }
 }
@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+
+use crate::conversion::quote_withdraw;
+use crate::errors::UrbaniumError;
+use crate::instructions::withdraw::{
+    accrue_withdraw_fees, held_shares, position_exit_fee_bps, settle_withdraw, withdrawn_event,
+    Withdraw,
+};
+
+pub fn handler(ctx: Context<Withdraw>, assets: u64, max_shares_burned: u64) -> Result<()> {
+    if assets == 0 {
+        return err!(UrbaniumError::ZeroAmount);
+    }
+
+    if ctx.accounts.token_program.key() != anchor_spl::token::ID {
+        return err!(UrbaniumError::InvalidTokenProgram);
+    }
+
+    let pre_totals = (
+        ctx.accounts.vault.total_assets,
+        ctx.accounts.vault.total_shares,
+    );
+
+    accrue_withdraw_fees(ctx.accounts, ctx.bumps.event_authority)?;
+
+    // The penalty applies once the burn reaches locked shares, which only
+    // the quote at the regular exit fee tells; requote at the fee it sets.
+    let fee_bps = position_exit_fee_bps(ctx.accounts, 0)?;
+    let quote = quote_withdraw(&ctx.accounts.vault, assets, fee_bps)?;
+    let fee_bps = position_exit_fee_bps(ctx.accounts, quote.amount)?;
+    let quote = quote_withdraw(&ctx.accounts.vault, assets, fee_bps)?;
+    let shares_burned = quote.amount;
+
+    if shares_burned > max_shares_burned {
+        return err!(UrbaniumError::SharesAboveMaximum);
+    }
+
+    if held_shares(ctx.accounts)? < shares_burned {
+        return err!(UrbaniumError::InsufficientShares);
+    }
+
+    let sources = settle_withdraw(ctx.accounts, shares_burned, assets)?;
+
+    let event = withdrawn_event(
+        ctx.accounts,
+        shares_burned,
+        assets,
+        quote.fee,
+        sources,
+        pre_totals,
+    );
+    emit_cpi!(event);
+
+    Ok(())
+}
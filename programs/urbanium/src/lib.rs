@@ -1,35 +1,185 @@
 use anchor_lang::prelude::*;
 
+pub mod conversion;
 pub mod errors;
+pub mod events;
+pub mod fees;
 pub mod instructions;
 pub mod oracle;
 pub mod pda;
 pub mod state;
 
+use conversion::Quote;
 use instructions::*;
 
 declare_id!("7XzKxpTmsiTevyC9KYaFZbpGp9NnJ2VwK6ie7RdKZXBW");
 
-#[program]
-pub mod urbanium {
-    use super::*;
+/// Expands to the `#[program]` module with `$extra` instructions appended.
+/// Anchor 0.30 ignores `#[cfg]` on instructions inside `#[program]`, so the
+/// `test-oracle` instructions are passed in here instead of being gated in
+/// place.
+macro_rules! urbanium_program {
+    ($($extra:tt)*) => {
+        #[program]
+        pub mod urbanium {
+            use super::*;
 
-    pub fn initialize_vault(
-        ctx: Context<InitializeVault>,
-        args: InitializeVaultArgs,
-    ) -> Result<()> {
-        instructions::initialize_vault::handler(ctx, args)
-    }
+            pub fn initialize_vault(
+                ctx: Context<InitializeVault>,
+                args: InitializeVaultArgs,
+            ) -> Result<()> {
+                instructions::initialize_vault::handler(ctx, args)
+            }
 
-    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
-        instructions::deposit::handler(ctx, amount)
-    }
+            pub fn deposit(ctx: Context<Deposit>, amount: u64, min_shares_out: u64) -> Result<()> {
+                instructions::deposit::handler(ctx, amount, min_shares_out)
+            }
+
+            pub fn deposit_for(
+                ctx: Context<Deposit>,
+                amount: u64,
+                min_shares_out: u64,
+            ) -> Result<()> {
+                instructions::deposit_for::handler(ctx, amount, min_shares_out)
+            }
+
+            pub fn withdraw(
+                ctx: Context<Withdraw>,
+                shares: u64,
+                min_amount_out: u64,
+                close_if_empty: bool,
+            ) -> Result<()> {
+                instructions::withdraw::handler(ctx, shares, min_amount_out, close_if_empty)
+            }
+
+            pub fn mint_shares(
+                ctx: Context<Deposit>,
+                shares: u64,
+                max_assets_in: u64,
+            ) -> Result<()> {
+                instructions::mint_shares::handler(ctx, shares, max_assets_in)
+            }
+
+            pub fn withdraw_assets(
+                ctx: Context<Withdraw>,
+                assets: u64,
+                max_shares_burned: u64,
+            ) -> Result<()> {
+                instructions::withdraw_assets::handler(ctx, assets, max_shares_burned)
+            }
+
+            pub fn route_yield(ctx: Context<RouteYield>, amount: u64) -> Result<()> {
+                instructions::route_yield::handler(ctx, amount)
+            }
+
+            pub fn donate(ctx: Context<Donate>, amount: u64) -> Result<()> {
+                instructions::donate::handler(ctx, amount)
+            }
+
+            pub fn sync_total_assets(ctx: Context<SyncTotalAssets>) -> Result<()> {
+                instructions::sync_total_assets::handler(ctx)
+            }
+
+            pub fn tokenize_position(ctx: Context<TokenizePosition>) -> Result<()> {
+                instructions::tokenize_position::handler(ctx)
+            }
+
+            pub fn close_position(ctx: Context<ClosePosition>) -> Result<()> {
+                instructions::close_position::handler(ctx)
+            }
+
+            pub fn preview_deposit(ctx: Context<Preview>, amount: u64) -> Result<Quote> {
+                instructions::preview_deposit::handler(ctx, amount)
+            }
+
+            pub fn preview_withdraw(ctx: Context<Preview>, shares: u64) -> Result<Quote> {
+                instructions::preview_withdraw::handler(ctx, shares)
+            }
+
+            pub fn convert_to_shares(ctx: Context<Preview>, assets: u64) -> Result<u64> {
+                instructions::convert_to_shares::handler(ctx, assets)
+            }
 
-    pub fn withdraw(ctx: Context<Withdraw>, shares: u64) -> Result<()> {
-        instructions::withdraw::handler(ctx, shares)
+            pub fn convert_to_assets(ctx: Context<Preview>, shares: u64) -> Result<u64> {
+                instructions::convert_to_assets::handler(ctx, shares)
+            }
+
+            pub fn max_deposit(ctx: Context<MaxDeposit>, owner: Pubkey) -> Result<u64> {
+                instructions::max_deposit::handler(ctx, owner)
+            }
+
+            pub fn max_withdraw(ctx: Context<MaxWithdraw>, owner: Pubkey) -> Result<u64> {
+                instructions::max_withdraw::handler(ctx, owner)
+            }
+
+            pub fn transfer_shares(ctx: Context<TransferShares>, amount: u64) -> Result<()> {
+                instructions::transfer_shares::handler(ctx, amount)
+            }
+
+            pub fn approve_delegate(
+                ctx: Context<ManageDelegate>,
+                delegate: Pubkey,
+                shares: u64,
+            ) -> Result<()> {
+                instructions::approve_delegate::handler(ctx, delegate, shares)
+            }
+
+            pub fn revoke_delegate(ctx: Context<ManageDelegate>) -> Result<()> {
+                instructions::revoke_delegate::handler(ctx)
+            }
+
+            pub fn accrue_fees(ctx: Context<AccrueFees>) -> Result<()> {
+                instructions::accrue_fees::handler(ctx)
+            }
+
+            pub fn request_withdraw(
+                ctx: Context<RequestWithdraw>,
+                shares: u64,
+                min_amount_out: u64,
+            ) -> Result<()> {
+                instructions::request_withdraw::handler(ctx, shares, min_amount_out)
+            }
+
+            pub fn claim_withdraw(ctx: Context<ClaimWithdraw>) -> Result<()> {
+                instructions::claim_withdraw::handler(ctx)
+            }
+
+            $($extra)*
+        }
+    };
+}
+
+#[cfg(not(feature = "test-oracle"))]
+urbanium_program!();
+
+#[cfg(feature = "test-oracle")]
+urbanium_program! {
+    pub fn init_mock_price(
+        ctx: Context<InitMockPrice>,
+        price: i64,
+        conf: u64,
+        expo: i32,
+    ) -> Result<()> {
+        instructions::init_mock_price::handler(ctx, price, conf, expo)
     }
 
-    pub fn route_yield(ctx: Context<RouteYield>, amount: u64) -> Result<()> {
-        instructions::route_yield::handler(ctx, amount)
+    pub fn set_mock_price(
+        ctx: Context<SetMockPrice>,
+        price: i64,
+        conf: u64,
+        expo: i32,
+        publish_time: i64,
+        ema_price: i64,
+        ema_conf: u64,
+    ) -> Result<()> {
+        instructions::set_mock_price::handler(
+            ctx,
+            price,
+            conf,
+            expo,
+            publish_time,
+            ema_price,
+            ema_conf,
+        )
     }
 }
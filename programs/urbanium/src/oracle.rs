@@ -1,7 +1,66 @@
 use anchor_lang::prelude::*;
-use pyth_sdk_solana::{load_price_feed_from_account_info, Price};
+use pyth_sdk_solana::state::SolanaPriceAccount;
+use pyth_sdk_solana::Price;
+use switchboard_on_demand::PullFeedAccountData;
 
 use crate::errors::UrbaniumError;
+use crate::fees::BPS_DENOMINATOR;
+#[cfg(feature = "test-oracle")]
+use crate::state::MockPrice;
+use crate::state::Vault;
+
+/// Decimals of every normalized price: one unit is `10^-PRICE_DECIMALS`.
+pub const PRICE_DECIMALS: u32 = 18;
+
+/// Account layout of `Vault::oracle_feed`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OracleKind {
+    /// Legacy Pyth push price account.
+    #[default]
+    PythLegacy,
+    /// Pyth pull `PriceUpdateV2` account written by the Pyth receiver program.
+    PythPull,
+    /// Switchboard On-Demand `PullFeedAccountData`.
+    SwitchboardOnDemand,
+    /// This program's `MockPrice`; only readable in `test-oracle` builds.
+    Mock,
+}
+
+/// A price feed of a vault besides its primary `oracle_feed`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OracleFeed {
+    pub kind: OracleKind,
+    /// Required owner of `feed`.
+    pub program: Pubkey,
+    pub feed: Pubkey,
+    /// Price feed id `feed` must carry; only checked for `PythPull`, whose
+    /// update accounts anyone can post for any feed.
+    pub feed_id: [u8; 32],
+}
+
+impl OracleFeed {
+    pub const LEN: usize = 1 + 32 + 32 + 32;
+}
+
+/// How a vault with secondary feeds combines their prices.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OracleAggregation {
+    /// Median of every usable feed.
+    Median,
+    /// First usable feed: the primary, then the secondaries in order.
+    Fallback,
+}
+
+/// Which of the feed's prices `route_yield` compares against the threshold.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PriceSource {
+    Spot,
+    Ema,
+    /// Lower of spot and EMA.
+    Min,
+    /// Higher of spot and EMA.
+    Max,
+}
 
 #[derive(Clone, Copy, Debug)]
 pub struct OraclePrice {
@@ -9,34 +68,440 @@ pub struct OraclePrice {
     pub conf: u64,
     pub expo: i32,
     pub publish_time: i64,
+    /// Exponential moving average published alongside the spot price, in the
+    /// same exponent. `None` for feeds without one (Switchboard).
+    pub ema: Option<EmaPrice>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct EmaPrice {
+    pub price: i64,
+    pub conf: u64,
+}
+
+impl OraclePrice {
+    /// Returns the price `source` selects, with its own confidence, as a
+    /// spot-only `OraclePrice` ready for `enforce_confidence_bps`.
+    pub fn select(self, source: PriceSource) -> Result<OraclePrice> {
+        let spot = EmaPrice {
+            price: self.price,
+            conf: self.conf,
+        };
+        let selected = match (source, self.ema) {
+            (PriceSource::Spot, _) => spot,
+            (_, None) => return err!(UrbaniumError::OracleEmaUnavailable),
+            (PriceSource::Ema, Some(ema)) => ema,
+            (PriceSource::Min, Some(ema)) if ema.price < spot.price => ema,
+            (PriceSource::Max, Some(ema)) if ema.price > spot.price => ema,
+            (PriceSource::Min | PriceSource::Max, Some(_)) => spot,
+        };
+
+        Ok(OraclePrice {
+            price: selected.price,
+            conf: selected.conf,
+            ema: None,
+            ..self
+        })
+    }
+
+    /// Rescales the spot price and confidence to `PRICE_DECIMALS` decimals.
+    /// Call after `select`; the EMA is dropped.
+    pub fn normalize(self) -> Result<NormalizedPrice> {
+        Ok(NormalizedPrice {
+            price: normalize_price(i128::from(self.price), self.expo)?,
+            conf: normalize_price(i128::from(self.conf), self.expo)?.unsigned_abs(),
+            publish_time: self.publish_time,
+        })
+    }
 }
 
-pub fn read_pyth_price(
-    oracle_program: &Pubkey,
+/// An oracle price rescaled to `PRICE_DECIMALS` decimals, so feeds with
+/// different exponents and `Vault::route_threshold_price` compare directly.
+#[derive(Clone, Copy, Debug)]
+pub struct NormalizedPrice {
+    pub price: i128,
+    pub conf: u128,
+    pub publish_time: i64,
+}
+
+/// Rescales `value * 10^expo` to `PRICE_DECIMALS` decimals, truncating
+/// toward zero when the feed publishes more decimals than that.
+pub fn normalize_price(value: i128, expo: i32) -> Result<i128> {
+    let shift = i64::from(expo) + i64::from(PRICE_DECIMALS);
+    let factor = u32::try_from(shift.unsigned_abs())
+        .ok()
+        .and_then(|exp| 10i128.checked_pow(exp));
+
+    if shift >= 0 {
+        factor
+            .and_then(|factor| value.checked_mul(factor))
+            .ok_or_else(|| error!(UrbaniumError::MathOverflow))
+    } else {
+        // Every `i128` is below `10^39`, so a divisor too large to represent
+        // truncates it to zero.
+        Ok(factor.map_or(0, |factor| value / factor))
+    }
+}
+
+/// Decodes one oracle account layout into an `OraclePrice`.
+pub trait OracleAdapter {
+    /// Reads the price in `feed`, rejecting it with `OracleStale` unless it
+    /// was published within `max_staleness_seconds` of `now`. Layouts that
+    /// name their price feed reject any other than `feed_id` with
+    /// `InvalidOracleFeed`. The feed owner is checked by `read_oracle_price`.
+    fn read_price(
+        feed: &AccountInfo,
+        feed_id: &[u8; 32],
+        now: i64,
+        max_staleness_seconds: u64,
+    ) -> Result<OraclePrice>;
+}
+
+/// Checks the owner of `oracle_feed_info` against `feed.program` and reads it
+/// with the adapter for `feed.kind`.
+pub fn read_oracle_price(
+    feed: &OracleFeed,
     oracle_feed_info: &AccountInfo,
     max_staleness_seconds: u64,
 ) -> Result<OraclePrice> {
-    if oracle_feed_info.owner != oracle_program {
+    if oracle_feed_info.owner != &feed.program {
         return err!(UrbaniumError::InvalidOracleOwner);
     }
 
-    let clock = Clock::get()?;
-    let price_feed = load_price_feed_from_account_info(oracle_feed_info)
-        .map_err(|_| error!(UrbaniumError::OraclePriceUnavailable))?;
+    let now = Clock::get()?.unix_timestamp;
+    let feed_id = &feed.feed_id;
+    match feed.kind {
+        OracleKind::PythLegacy => {
+            PythLegacyAdapter::read_price(oracle_feed_info, feed_id, now, max_staleness_seconds)
+        }
+        OracleKind::PythPull => {
+            PythPullAdapter::read_price(oracle_feed_info, feed_id, now, max_staleness_seconds)
+        }
+        OracleKind::SwitchboardOnDemand => {
+            SwitchboardAdapter::read_price(oracle_feed_info, feed_id, now, max_staleness_seconds)
+        }
+        #[cfg(feature = "test-oracle")]
+        OracleKind::Mock => {
+            MockAdapter::read_price(oracle_feed_info, feed_id, now, max_staleness_seconds)
+        }
+        #[cfg(not(feature = "test-oracle"))]
+        OracleKind::Mock => err!(UrbaniumError::MockOracleDisabled),
+    }
+}
+
+fn ensure_fresh(price: OraclePrice, now: i64, max_staleness_seconds: u64) -> Result<OraclePrice> {
+    if now.abs_diff(price.publish_time) > max_staleness_seconds {
+        return err!(UrbaniumError::OracleStale);
+    }
+
+    Ok(price)
+}
+
+pub struct PythLegacyAdapter;
+
+impl OracleAdapter for PythLegacyAdapter {
+    fn read_price(
+        feed: &AccountInfo,
+        _feed_id: &[u8; 32],
+        now: i64,
+        max_staleness_seconds: u64,
+    ) -> Result<OraclePrice> {
+        let price_feed = SolanaPriceAccount::account_info_to_feed(feed)
+            .map_err(|_| error!(UrbaniumError::OraclePriceUnavailable))?;
+
+        let maybe: Option<Price> = price_feed.get_price_no_older_than(now, max_staleness_seconds);
+
+        let price = maybe.ok_or_else(|| error!(UrbaniumError::OracleStale))?;
+        // Published together with the spot price, so equally fresh.
+        let ema = price_feed.get_ema_price_unchecked();
+
+        Ok(OraclePrice {
+            price: price.price,
+            conf: price.conf,
+            expo: price.expo,
+            publish_time: price.publish_time,
+            ema: Some(EmaPrice {
+                price: ema.price,
+                conf: ema.conf,
+            }),
+        })
+    }
+}
+
+/// `sha256("account:PriceUpdateV2")[..8]`.
+const PRICE_UPDATE_V2_DISCRIMINATOR: [u8; 8] = [34, 241, 35, 99, 157, 126, 244, 205];
+
+/// Mirror of the Pyth receiver's `PriceUpdateV2`, which would otherwise pull
+/// in a second Anchor version.
+#[derive(AnchorDeserialize)]
+struct PriceUpdateV2 {
+    _write_authority: Pubkey,
+    verification_level: VerificationLevel,
+    price_message: PriceFeedMessage,
+    _posted_slot: u64,
+}
+
+#[derive(AnchorDeserialize, PartialEq, Eq)]
+enum VerificationLevel {
+    Partial { _num_signatures: u8 },
+    Full,
+}
+
+#[derive(AnchorDeserialize)]
+struct PriceFeedMessage {
+    feed_id: [u8; 32],
+    price: i64,
+    conf: u64,
+    exponent: i32,
+    publish_time: i64,
+    _prev_publish_time: i64,
+    ema_price: i64,
+    ema_conf: u64,
+}
+
+pub struct PythPullAdapter;
+
+impl OracleAdapter for PythPullAdapter {
+    fn read_price(
+        feed: &AccountInfo,
+        feed_id: &[u8; 32],
+        now: i64,
+        max_staleness_seconds: u64,
+    ) -> Result<OraclePrice> {
+        let data = feed.try_borrow_data()?;
+        let update = data
+            .strip_prefix(&PRICE_UPDATE_V2_DISCRIMINATOR)
+            .and_then(|mut body| PriceUpdateV2::deserialize(&mut body).ok())
+            .ok_or_else(|| error!(UrbaniumError::OraclePriceUnavailable))?;
+
+        // Partially verified updates carry fewer guardian signatures than the
+        // Wormhole quorum.
+        if update.verification_level != VerificationLevel::Full {
+            return err!(UrbaniumError::OraclePriceUnavailable);
+        }
+
+        let message = update.price_message;
+        if message.feed_id != *feed_id {
+            return err!(UrbaniumError::InvalidOracleFeed);
+        }
+
+        ensure_fresh(
+            OraclePrice {
+                price: message.price,
+                conf: message.conf,
+                expo: message.exponent,
+                publish_time: message.publish_time,
+                ema: Some(EmaPrice {
+                    price: message.ema_price,
+                    conf: message.ema_conf,
+                }),
+            },
+            now,
+            max_staleness_seconds,
+        )
+    }
+}
+
+/// Decimals of Switchboard's fixed-point results.
+pub const SWITCHBOARD_DECIMALS: i32 = 18;
+
+/// Rescales a Switchboard result to the most decimals at which both `value`
+/// and `std_dev` fit an `OraclePrice`, truncating toward zero. Results below
+/// `i64::MAX * 10^-18` (about 9.2) keep all 18; larger ones give up one
+/// decimal per factor of ten. Returns the price, confidence and exponent.
+fn switchboard_price(value: i128, std_dev: i128) -> (i64, u64, i32) {
+    let mut value = value;
+    let mut std_dev = std_dev.unsigned_abs();
+    let mut expo = -SWITCHBOARD_DECIMALS;
+    loop {
+        if let (Ok(price), Ok(conf)) = (i64::try_from(value), u64::try_from(std_dev)) {
+            return (price, conf, expo);
+        }
+
+        value /= 10;
+        std_dev /= 10;
+        expo += 1;
+    }
+}
+
+pub struct SwitchboardAdapter;
+
+impl OracleAdapter for SwitchboardAdapter {
+    fn read_price(
+        feed: &AccountInfo,
+        _feed_id: &[u8; 32],
+        now: i64,
+        max_staleness_seconds: u64,
+    ) -> Result<OraclePrice> {
+        let data = feed.try_borrow_data()?;
+        let pull_feed = PullFeedAccountData::parse(data)
+            .map_err(|_| error!(UrbaniumError::OraclePriceUnavailable))?;
+
+        if pull_feed.result.num_samples == 0 {
+            return err!(UrbaniumError::OraclePriceUnavailable);
+        }
+
+        let (price, conf, expo) =
+            switchboard_price(pull_feed.result.value, pull_feed.result.std_dev);
+
+        ensure_fresh(
+            OraclePrice {
+                price,
+                conf,
+                expo,
+                publish_time: pull_feed.result_ts(),
+                ema: None,
+            },
+            now,
+            max_staleness_seconds,
+        )
+    }
+}
+
+#[cfg(feature = "test-oracle")]
+pub struct MockAdapter;
+
+#[cfg(feature = "test-oracle")]
+impl OracleAdapter for MockAdapter {
+    fn read_price(
+        feed: &AccountInfo,
+        _feed_id: &[u8; 32],
+        now: i64,
+        max_staleness_seconds: u64,
+    ) -> Result<OraclePrice> {
+        if feed.owner != &crate::ID {
+            return err!(UrbaniumError::InvalidOracleOwner);
+        }
+
+        let data = feed.try_borrow_data()?;
+        let mock_price = MockPrice::try_deserialize(&mut &data[..])?;
+
+        ensure_fresh(
+            OraclePrice {
+                price: mock_price.price,
+                conf: mock_price.conf,
+                expo: mock_price.expo,
+                publish_time: mock_price.publish_time,
+                ema: Some(EmaPrice {
+                    price: mock_price.ema_price,
+                    conf: mock_price.ema_conf,
+                }),
+            },
+            now,
+            max_staleness_seconds,
+        )
+    }
+}
+
+/// Reads the price of `vault` from `oracle_feed` and, when the vault has
+/// secondary feeds, from `secondary_feeds` in `secondary_oracles` order.
+///
+/// With secondary feeds, a feed that fails any check is skipped rather than
+/// failing the instruction; at least `oracle_quorum` must remain.
+pub fn read_vault_price(
+    vault: &Vault,
+    oracle_feed: &AccountInfo,
+    secondary_feeds: &[AccountInfo],
+) -> Result<NormalizedPrice> {
+    let primary = read_feed_price(vault, &vault.primary_oracle(), oracle_feed);
+    let secondaries = vault.secondary_oracles();
+    if secondaries.is_empty() {
+        return primary;
+    }
+
+    if secondary_feeds.len() < secondaries.len() {
+        return err!(UrbaniumError::InvalidOracleFeed);
+    }
+
+    let mut prices: Vec<NormalizedPrice> = primary.into_iter().collect();
+    for (feed, info) in secondaries.iter().zip(secondary_feeds) {
+        if info.key() != feed.feed {
+            return err!(UrbaniumError::InvalidOracleFeed);
+        }
+
+        if let Ok(price) = read_feed_price(vault, feed, info) {
+            prices.push(price);
+        }
+    }
+
+    aggregate_prices(vault, &prices)
+}
+
+/// Reads one feed, applies the vault's price source and confidence bound,
+/// and normalizes the result.
+pub fn read_feed_price(
+    vault: &Vault,
+    feed: &OracleFeed,
+    info: &AccountInfo,
+) -> Result<NormalizedPrice> {
+    let price =
+        read_oracle_price(feed, info, vault.max_staleness_seconds)?.select(vault.price_source)?;
+
+    enforce_confidence_bps(price, vault.max_confidence_bps)?;
+
+    price.normalize()
+}
+
+/// Combines usable feed prices, primary first, per `oracle_aggregation`.
+/// Every price must lie within `max_oracle_divergence_bps` of the result.
+pub fn aggregate_prices(vault: &Vault, prices: &[NormalizedPrice]) -> Result<NormalizedPrice> {
+    if prices.is_empty() || prices.len() < usize::from(vault.oracle_quorum) {
+        return err!(UrbaniumError::OracleQuorumNotMet);
+    }
+
+    let price = match vault.oracle_aggregation {
+        OracleAggregation::Median => median_price(prices)?,
+        OracleAggregation::Fallback => prices[0],
+    };
+
+    if vault.max_oracle_divergence_bps > 0 {
+        let reference = price.price.unsigned_abs();
+        if reference == 0 {
+            return err!(UrbaniumError::OraclePriceUnavailable);
+        }
+
+        for other in prices {
+            let divergence_bps = other
+                .price
+                .abs_diff(price.price)
+                .checked_mul(BPS_DENOMINATOR)
+                .ok_or_else(|| error!(UrbaniumError::MathOverflow))?
+                / reference;
+            if divergence_bps > u128::from(vault.max_oracle_divergence_bps) {
+                return err!(UrbaniumError::OracleDivergenceTooHigh);
+            }
+        }
+    }
+
+    Ok(price)
+}
+
+/// Median by price; an even count averages the middle two (rounded toward
+/// negative infinity) and keeps the wider confidence. Reports the oldest
+/// publish time of the inputs.
+fn median_price(prices: &[NormalizedPrice]) -> Result<NormalizedPrice> {
+    let mut sorted = prices.to_vec();
+    sorted.sort_by_key(|price| price.price);
 
-    let maybe: Option<Price> = price_feed.get_price_no_older_than(
-        clock.unix_timestamp,
-        max_staleness_seconds,
-    );
+    let mid = sorted.len() / 2;
+    let mut median = sorted[mid];
+    if sorted.len().is_multiple_of(2) {
+        let lower = sorted[mid - 1];
+        median.price = lower
+            .price
+            .checked_add(median.price)
+            .ok_or_else(|| error!(UrbaniumError::MathOverflow))?
+            .div_euclid(2);
+        median.conf = median.conf.max(lower.conf);
+    }
 
-    let price = maybe.ok_or_else(|| error!(UrbaniumError::OracleStale))?;
+    median.publish_time = prices
+        .iter()
+        .map(|price| price.publish_time)
+        .min()
+        .unwrap_or(median.publish_time);
 
-    Ok(OraclePrice {
-        price: price.price,
-        conf: price.conf,
-        expo: price.expo,
-        publish_time: price.publish_time,
-    })
+    Ok(median)
 }
 
 pub fn enforce_confidence_bps(price: OraclePrice, max_confidence_bps: u16) -> Result<()> {
@@ -58,3 +523,322 @@ pub fn enforce_confidence_bps(price: OraclePrice, max_confidence_bps: u16) -> Re
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn price_update(feed_id: [u8; 32], publish_time: i64) -> Vec<u8> {
+        let mut data = PRICE_UPDATE_V2_DISCRIMINATOR.to_vec();
+        data.extend_from_slice(&[0; 32]); // write authority
+        data.push(1); // VerificationLevel::Full
+        data.extend_from_slice(&feed_id);
+        data.extend_from_slice(&125_000_000i64.to_le_bytes());
+        data.extend_from_slice(&50_000u64.to_le_bytes());
+        data.extend_from_slice(&(-8i32).to_le_bytes());
+        data.extend_from_slice(&publish_time.to_le_bytes());
+        data.extend_from_slice(&publish_time.to_le_bytes()); // prev publish time
+        data.extend_from_slice(&124_000_000i64.to_le_bytes());
+        data.extend_from_slice(&60_000u64.to_le_bytes());
+        data.extend_from_slice(&0u64.to_le_bytes()); // posted slot
+        data
+    }
+
+    fn read_pyth_pull(data: &mut [u8], feed_id: &[u8; 32]) -> Result<OraclePrice> {
+        let key = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let mut lamports = 0;
+        let info = AccountInfo::new(&key, false, false, &mut lamports, data, &owner, false, 0);
+        PythPullAdapter::read_price(&info, feed_id, 1_000, 60)
+    }
+
+    #[test]
+    fn pyth_pull_accepts_configured_feed_id() {
+        let mut data = price_update([7; 32], 1_000);
+        let price = read_pyth_pull(&mut data, &[7; 32]).unwrap();
+
+        assert_eq!(price.price, 125_000_000);
+        assert_eq!(price.expo, -8);
+        assert_eq!(price.ema.unwrap().price, 124_000_000);
+    }
+
+    #[test]
+    fn pyth_pull_rejects_other_feed_id() {
+        let mut data = price_update([8; 32], 1_000);
+
+        assert_eq!(
+            read_pyth_pull(&mut data, &[7; 32]).unwrap_err(),
+            error!(UrbaniumError::InvalidOracleFeed)
+        );
+    }
+
+    /// Spot 100 ± 1 with, optionally, an EMA of `ema` ± 2.
+    fn oracle_price(ema: Option<i64>) -> OraclePrice {
+        OraclePrice {
+            price: 100,
+            conf: 1,
+            expo: -2,
+            publish_time: 1_000,
+            ema: ema.map(|price| EmaPrice { price, conf: 2 }),
+        }
+    }
+
+    fn selected(price: OraclePrice, source: PriceSource) -> (i64, u64) {
+        let selected = price.select(source).unwrap();
+        assert!(selected.ema.is_none());
+        assert_eq!((selected.expo, selected.publish_time), (-2, 1_000));
+        (selected.price, selected.conf)
+    }
+
+    #[test]
+    fn select_carries_the_confidence_of_the_chosen_price() {
+        let below = oracle_price(Some(90));
+        assert_eq!(selected(below, PriceSource::Spot), (100, 1));
+        assert_eq!(selected(below, PriceSource::Ema), (90, 2));
+        assert_eq!(selected(below, PriceSource::Min), (90, 2));
+        assert_eq!(selected(below, PriceSource::Max), (100, 1));
+
+        let above = oracle_price(Some(110));
+        assert_eq!(selected(above, PriceSource::Min), (100, 1));
+        assert_eq!(selected(above, PriceSource::Max), (110, 2));
+
+        // Ties keep the spot price.
+        let equal = oracle_price(Some(100));
+        assert_eq!(selected(equal, PriceSource::Min), (100, 1));
+        assert_eq!(selected(equal, PriceSource::Max), (100, 1));
+    }
+
+    #[test]
+    fn select_requires_an_ema_unless_spot() {
+        let spot_only = oracle_price(None);
+        assert_eq!(selected(spot_only, PriceSource::Spot), (100, 1));
+
+        for source in [PriceSource::Ema, PriceSource::Min, PriceSource::Max] {
+            assert_eq!(
+                spot_only.select(source).unwrap_err(),
+                error!(UrbaniumError::OracleEmaUnavailable)
+            );
+        }
+    }
+
+    #[test]
+    fn normalize_price_scales_common_exponents() {
+        // 1.25 at Pyth's usual exponent and at 18 decimals.
+        assert_eq!(
+            normalize_price(125_000_000, -8).unwrap(),
+            1_250_000_000_000_000_000
+        );
+        assert_eq!(
+            normalize_price(1_250_000_000_000_000_000, -18).unwrap(),
+            1_250_000_000_000_000_000
+        );
+        assert_eq!(normalize_price(-3, 0).unwrap(), -3_000_000_000_000_000_000);
+    }
+
+    #[test]
+    fn normalize_price_rejects_positive_exponent_overflow() {
+        assert_eq!(normalize_price(5, 2).unwrap(), 500_000_000_000_000_000_000);
+        assert_eq!(
+            normalize_price(i128::from(i64::MAX), 2).unwrap_err(),
+            error!(UrbaniumError::MathOverflow)
+        );
+        // 10^39 itself does not fit.
+        assert_eq!(
+            normalize_price(1, 21).unwrap_err(),
+            error!(UrbaniumError::MathOverflow)
+        );
+    }
+
+    #[test]
+    fn normalize_price_truncates_extra_decimals_toward_zero() {
+        assert_eq!(normalize_price(1_999, -21).unwrap(), 1);
+        assert_eq!(normalize_price(-1_999, -21).unwrap(), -1);
+        assert_eq!(normalize_price(999, -21).unwrap(), 0);
+    }
+
+    #[test]
+    fn normalize_price_of_tiny_exponent_is_zero() {
+        assert_eq!(normalize_price(i128::MAX, -57).unwrap(), 0);
+        assert_eq!(normalize_price(i128::MIN, -100).unwrap(), 0);
+        assert_eq!(normalize_price(5, i32::MIN).unwrap(), 0);
+    }
+
+    const SWITCHBOARD_FEED_LEN: usize = 8 + core::mem::size_of::<PullFeedAccountData>();
+
+    /// Keeps the feed body 16-byte aligned behind its 8-byte discriminator,
+    /// as `PullFeedAccountData::parse` requires.
+    #[repr(C, align(16))]
+    struct SwitchboardFeed([u8; 8 + SWITCHBOARD_FEED_LEN]);
+
+    fn read_switchboard(value: i128, std_dev: i128, num_samples: u8) -> Result<OraclePrice> {
+        use core::mem::offset_of;
+        use switchboard_on_demand::CurrentResult;
+
+        let mut feed = Box::new(SwitchboardFeed([0; 8 + SWITCHBOARD_FEED_LEN]));
+        let data = &mut feed.0[8..];
+        data[..8].copy_from_slice(&[196, 27, 108, 196, 10, 215, 219, 40]);
+
+        let result = 8 + offset_of!(PullFeedAccountData, result);
+        let value_at = result + offset_of!(CurrentResult, value);
+        data[value_at..value_at + 16].copy_from_slice(&value.to_le_bytes());
+        let std_dev_at = result + offset_of!(CurrentResult, std_dev);
+        data[std_dev_at..std_dev_at + 16].copy_from_slice(&std_dev.to_le_bytes());
+        data[result + offset_of!(CurrentResult, num_samples)] = num_samples;
+        // The median is submission 0.
+        let timestamps = 8 + offset_of!(PullFeedAccountData, submission_timestamps);
+        data[timestamps..timestamps + 8].copy_from_slice(&1_000i64.to_le_bytes());
+
+        let key = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let mut lamports = 0;
+        let info = AccountInfo::new(&key, false, false, &mut lamports, data, &owner, false, 0);
+        SwitchboardAdapter::read_price(&info, &[0; 32], 1_000, 60)
+    }
+
+    #[test]
+    fn switchboard_keeps_all_decimals_of_small_prices() {
+        // 0.000000001234 ± 0.000000000005, below the 10^-8 a fixed exponent
+        // would keep.
+        let price = read_switchboard(1_234_000_000, 5_000_000, 1).unwrap();
+
+        assert_eq!(
+            (price.price, price.conf, price.expo),
+            (1_234_000_000, 5_000_000, -18)
+        );
+        assert_eq!(price.publish_time, 1_000);
+        assert!(price.ema.is_none());
+
+        let normalized = price.normalize().unwrap();
+        assert_eq!(
+            (normalized.price, normalized.conf),
+            (1_234_000_000, 5_000_000)
+        );
+    }
+
+    #[test]
+    fn switchboard_drops_only_the_decimals_that_overflow() {
+        // 65_000.123456789012345678 ± 0.000000000001234567.
+        let value = 65_000_123_456_789_012_345_678i128;
+        let price = read_switchboard(value, 1_234_567, 1).unwrap();
+
+        assert_eq!(
+            (price.price, price.conf, price.expo),
+            (6_500_012_345_678_901_234, 123, -14)
+        );
+        assert_eq!(
+            price.normalize().unwrap().price,
+            65_000_123_456_789_012_340_000
+        );
+    }
+
+    #[test]
+    fn switchboard_rejects_empty_result() {
+        assert_eq!(
+            read_switchboard(0, 0, 0).unwrap_err(),
+            error!(UrbaniumError::OraclePriceUnavailable)
+        );
+    }
+
+    fn aggregating_vault(
+        aggregation: OracleAggregation,
+        quorum: u8,
+        max_divergence_bps: u16,
+    ) -> Vault {
+        let mut vault = Vault::zeroed();
+        vault.oracle_aggregation = aggregation;
+        vault.oracle_quorum = quorum;
+        vault.max_oracle_divergence_bps = max_divergence_bps;
+        vault
+    }
+
+    fn price(price: i128, conf: u128, publish_time: i64) -> NormalizedPrice {
+        NormalizedPrice {
+            price,
+            conf,
+            publish_time,
+        }
+    }
+
+    #[test]
+    fn median_of_odd_count_takes_middle_price_and_oldest_time() {
+        let vault = aggregating_vault(OracleAggregation::Median, 3, 0);
+        let prices = [price(100, 1, 30), price(300, 3, 10), price(200, 2, 20)];
+
+        let median = aggregate_prices(&vault, &prices).unwrap();
+        assert_eq!(median.price, 200);
+        assert_eq!(median.conf, 2);
+        assert_eq!(median.publish_time, 10);
+    }
+
+    #[test]
+    fn median_of_even_count_averages_middle_prices() {
+        let vault = aggregating_vault(OracleAggregation::Median, 2, 0);
+
+        let median = aggregate_prices(&vault, &[price(201, 5, 20), price(100, 9, 10)]).unwrap();
+        assert_eq!(median.price, 150);
+        assert_eq!(median.conf, 9);
+        assert_eq!(median.publish_time, 10);
+
+        // Rounded toward negative infinity.
+        let median = aggregate_prices(&vault, &[price(-3, 0, 0), price(0, 0, 0)]).unwrap();
+        assert_eq!(median.price, -2);
+    }
+
+    #[test]
+    fn fallback_takes_first_usable_price() {
+        let vault = aggregating_vault(OracleAggregation::Fallback, 1, 0);
+        let prices = [price(300, 3, 30), price(100, 1, 10), price(200, 2, 20)];
+
+        let first = aggregate_prices(&vault, &prices).unwrap();
+        assert_eq!(first.price, 300);
+        assert_eq!(first.publish_time, 30);
+    }
+
+    #[test]
+    fn quorum_not_met() {
+        let vault = aggregating_vault(OracleAggregation::Median, 3, 0);
+        assert_eq!(
+            aggregate_prices(&vault, &[price(100, 1, 0), price(101, 1, 0)]).unwrap_err(),
+            error!(UrbaniumError::OracleQuorumNotMet)
+        );
+
+        // No usable feed never meets a quorum, even a zero one.
+        let vault = aggregating_vault(OracleAggregation::Fallback, 0, 0);
+        assert_eq!(
+            aggregate_prices(&vault, &[]).unwrap_err(),
+            error!(UrbaniumError::OracleQuorumNotMet)
+        );
+    }
+
+    #[test]
+    fn divergence_limit_bounds_every_feed() {
+        let vault = aggregating_vault(OracleAggregation::Median, 1, 100);
+
+        // 102 is 99 bps (rounded down) above the median of 101.
+        let prices = [price(100, 0, 0), price(101, 0, 0), price(102, 0, 0)];
+        assert_eq!(aggregate_prices(&vault, &prices).unwrap().price, 101);
+
+        let prices = [price(100, 0, 0), price(100, 0, 0), price(110, 0, 0)];
+        assert_eq!(
+            aggregate_prices(&vault, &prices).unwrap_err(),
+            error!(UrbaniumError::OracleDivergenceTooHigh)
+        );
+
+        // Fallback prices are bounded against the first feed.
+        let vault = aggregating_vault(OracleAggregation::Fallback, 1, 100);
+        assert_eq!(
+            aggregate_prices(&vault, &[price(100, 0, 0), price(102, 0, 0)]).unwrap_err(),
+            error!(UrbaniumError::OracleDivergenceTooHigh)
+        );
+    }
+
+    #[test]
+    fn divergence_limit_rejects_zero_price() {
+        let vault = aggregating_vault(OracleAggregation::Fallback, 1, 100);
+
+        assert_eq!(
+            aggregate_prices(&vault, &[price(0, 0, 0), price(1, 0, 0)]).unwrap_err(),
+            error!(UrbaniumError::OraclePriceUnavailable)
+        );
+    }
+}
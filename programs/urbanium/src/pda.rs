@@ -3,9 +3,21 @@ use anchor_lang::prelude::*;
 pub const VAULT_SEED: &[u8] = b"urbanium_vault";
 pub const VAULT_AUTHORITY_SEED: &[u8] = b"urbanium_vault_authority";
 pub const USER_POSITION_SEED: &[u8] = b"urbanium_user_position";
+pub const TOKEN_ACCOUNT_SEED: &[u8] = b"urbanium_token_account";
+pub const SHARE_MINT_SEED: &[u8] = b"urbanium_share_mint";
+pub const WITHDRAW_REQUEST_SEED: &[u8] = b"urbanium_withdraw_request";
 
-pub fn vault_pda(mint: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
-    Pubkey::find_program_address(&[VAULT_SEED, mint.as_ref()], program_id)
+/// Anchor `#[event_cpi]` authority seed.
+pub const EVENT_AUTHORITY_SEED: &[u8] = b"__event_authority";
+
+/// Role bytes distinguishing the vault-controlled token accounts derived from
+/// `TOKEN_ACCOUNT_SEED` under a single vault authority.
+pub const PRIMARY_TOKEN_ACCOUNT_ROLE: u8 = 0;
+pub const YIELD_A_TOKEN_ACCOUNT_ROLE: u8 = 1;
+pub const YIELD_B_TOKEN_ACCOUNT_ROLE: u8 = 2;
+
+pub fn vault_pda(mint: &Pubkey, creator: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[VAULT_SEED, mint.as_ref(), creator.as_ref()], program_id)
 }
 
 pub fn vault_authority_pda(vault: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
@@ -18,3 +30,33 @@ pub fn user_position_pda(vault: &Pubkey, owner: &Pubkey, program_id: &Pubkey) ->
         program_id,
     )
 }
+
+pub fn share_mint_pda(vault: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[SHARE_MINT_SEED, vault.as_ref()], program_id)
+}
+
+pub fn withdraw_request_pda(vault: &Pubkey, id: u64, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[WITHDRAW_REQUEST_SEED, vault.as_ref(), &id.to_le_bytes()],
+        program_id,
+    )
+}
+
+pub fn token_account_pda(vault_authority: &Pubkey, role: u8, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[TOKEN_ACCOUNT_SEED, vault_authority.as_ref(), &[role]],
+        program_id,
+    )
+}
+
+pub fn vault_token_account_pda(vault_authority: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    token_account_pda(vault_authority, PRIMARY_TOKEN_ACCOUNT_ROLE, program_id)
+}
+
+pub fn yield_token_account_a_pda(vault_authority: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    token_account_pda(vault_authority, YIELD_A_TOKEN_ACCOUNT_ROLE, program_id)
+}
+
+pub fn yield_token_account_b_pda(vault_authority: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    token_account_pda(vault_authority, YIELD_B_TOKEN_ACCOUNT_ROLE, program_id)
+}
@@ -1,10 +1,20 @@
 use anchor_lang::prelude::*;
 
+use crate::errors::UrbaniumError;
+use crate::fees::PRICE_SCALE;
+use crate::oracle::{NormalizedPrice, OracleAggregation, OracleFeed, OracleKind, PriceSource};
+
 #[account]
 pub struct Vault {
+    /// Layout version, set to `Vault::VERSION` at initialization. Every
+    /// instruction rejects vaults of any other version.
     pub version: u8,
     pub bump: u8,
     pub authority_bump: u8,
+    pub vault_token_account_bump: u8,
+    pub yield_token_account_a_bump: u8,
+    pub yield_token_account_b_bump: u8,
+    pub share_mint_bump: u8,
 
     pub mint: Pubkey,
 
@@ -12,23 +22,307 @@ pub struct Vault {
     pub yield_token_account_a: Pubkey,
     pub yield_token_account_b: Pubkey,
 
+    /// `Pubkey::default()` when shares are tracked only in `UserPosition`.
+    pub share_mint: Pubkey,
+
     pub oracle_program: Pubkey,
     pub oracle_feed: Pubkey,
+    /// Exponent of `oracle_feed` at initialization. Informational only:
+    /// prices are normalized by `oracle::normalize_price` before use.
     pub oracle_expo: i32,
 
     pub max_staleness_seconds: u64,
     pub max_confidence_bps: u16,
 
-    pub route_threshold_price: i64,
+    /// Compared against the oracle price in `oracle::PRICE_DECIMALS` decimals.
+    pub route_threshold_price: i128,
 
+    pub total_assets: u64,
     pub total_shares: u64,
+    pub decimals_offset: u8,
+
+    /// Owner of the `UserPosition` credited with fee shares.
+    pub fee_recipient: Pubkey,
+    /// Annualized, accrued per second.
+    pub management_fee_bps: u16,
+    /// Charged on share price gains above `high_water_mark`.
+    pub performance_fee_bps: u16,
+    /// Highest post-fee share price seen, scaled by `fees::PRICE_SCALE`.
+    pub high_water_mark: u128,
+    pub last_fee_accrual: i64,
+
+    /// Deposit caps in assets; `0` disables the cap.
+    pub max_total_assets: u64,
+    pub max_assets_per_user: u64,
+
+    /// Assets owed to unclaimed `WithdrawRequest`s; held in the vault token
+    /// accounts but excluded from `total_assets`.
+    pub queued_assets: u64,
+    /// Id of the oldest unclaimed request; claims must match it (FIFO).
+    pub withdraw_queue_head: u64,
+    /// Id assigned to the next request.
+    pub withdraw_queue_tail: u64,
+
+    /// Entry and exit fees, charged inside the share conversion. The fee
+    /// assets stay in the vault and accrue to the remaining shareholders.
+    pub deposit_fee_bps: u16,
+    pub withdraw_fee_bps: u16,
+
+    /// Withdrawals from a position within `min_hold_seconds` of its last
+    /// deposit are rejected, or charged `early_exit_penalty_bps` on top of
+    /// the exit fee when that is non-zero. `0` disables the hold.
+    pub min_hold_seconds: u64,
+    pub early_exit_penalty_bps: u16,
+
+    pub stats: VaultStats,
+
+    /// Layout of `oracle_feed`; selects the adapter that reads it.
+    pub oracle_kind: OracleKind,
+    /// Price `route_yield` compares against `route_threshold_price`.
+    pub price_source: PriceSource,
+
+    /// Feeds read alongside `oracle_feed`; only the first
+    /// `secondary_oracle_count` are set.
+    pub secondary_oracles: [OracleFeed; Vault::MAX_SECONDARY_ORACLES],
+    pub secondary_oracle_count: u8,
+    /// Usable feeds required when secondary feeds are configured.
+    pub oracle_quorum: u8,
+    pub oracle_aggregation: OracleAggregation,
+    /// Largest spread between any usable feed and the aggregated price;
+    /// `0` disables the check.
+    pub max_oracle_divergence_bps: u16,
+
+    /// Route on the confidence interval instead of the price alone: toward
+    /// `yield_token_account_a` only if `price - conf` clears the bound, and
+    /// toward `yield_token_account_b` only if `price + conf` falls below it.
+    pub conservative_routing: bool,
+    /// Hysteresis band around `route_threshold_price`, in the same units.
+    /// Once routed, the destination only switches to B below
+    /// `route_lower_price` and back to A at or above `route_upper_price`.
+    pub route_lower_price: i128,
+    pub route_upper_price: i128,
+    /// Destination of the last `route_yield`; `Unset` until the first one.
+    pub last_route_destination: RouteDestination,
+
+    /// Price feed id of `oracle_feed`; see `OracleFeed::feed_id`.
+    pub oracle_feed_id: [u8; 32],
+
+    /// Signer of `initialize_vault`; seeds the vault PDA with `mint`, so
+    /// each creator configures its own vault for a mint.
+    pub creator: Pubkey,
+}
+
+impl Vault {
+    /// Bumped on every incompatible layout or semantics change. Version 2
+    /// derives the primary and yield token accounts as role-seeded PDAs;
+    /// version 3 adds `creator` to the vault seeds; version 4 replaces the
+    /// averaged hold time of positions with `locked_shares`.
+    pub const VERSION: u8 = 4;
+
+    pub const MAX_DECIMALS_OFFSET: u8 = 9;
+    pub const VIRTUAL_ASSETS: u128 = 1;
+
+    pub const MAX_MANAGEMENT_FEE_BPS: u16 = 500;
+    pub const MAX_PERFORMANCE_FEE_BPS: u16 = 5_000;
+    pub const MAX_DEPOSIT_FEE_BPS: u16 = 1_000;
+    pub const MAX_WITHDRAW_FEE_BPS: u16 = 1_000;
+    pub const MAX_EARLY_EXIT_PENALTY_BPS: u16 = 1_000;
+
+    pub const MAX_SECONDARY_ORACLES: usize = 4;
+
+    pub const LEN: usize = 1
+        + 1
+        + 1
+        + 1
+        + 1
+        + 1
+        + 1
+        + 32
+        + 32
+        + 32
+        + 32
+        + 32
+        + 32
+        + 32
+        + 4
+        + 8
+        + 2
+        + 16
+        + 8
+        + 8
+        + 1
+        + 32
+        + 2
+        + 2
+        + 16
+        + 8
+        + 8
+        + 8
+        + 8
+        + 8
+        + 8
+        + 2
+        + 2
+        + 8
+        + 2
+        + VaultStats::LEN
+        + 1
+        + 1
+        + OracleFeed::LEN * Vault::MAX_SECONDARY_ORACLES
+        + 1
+        + 1
+        + 1
+        + 2
+        + 1
+        + 16
+        + 16
+        + 1
+        + 32
+        + 32;
+
+    /// Virtual shares (`10^decimals_offset`) added to the share supply when
+    /// pricing, paired with one virtual asset unit. This keeps the first
+    /// depositor from inflating the share price through direct donations.
+    pub fn virtual_shares(&self) -> u128 {
+        10u128.pow(u32::from(self.decimals_offset))
+    }
+
+    pub fn is_tokenized(&self) -> bool {
+        self.share_mint != Pubkey::default()
+    }
+
+    pub fn primary_oracle(&self) -> OracleFeed {
+        OracleFeed {
+            kind: self.oracle_kind,
+            program: self.oracle_program,
+            feed: self.oracle_feed,
+            feed_id: self.oracle_feed_id,
+        }
+    }
+
+    pub fn secondary_oracles(&self) -> &[OracleFeed] {
+        let count = usize::from(self.secondary_oracle_count).min(Vault::MAX_SECONDARY_ORACLES);
+        &self.secondary_oracles[..count]
+    }
+
+    /// Picks the `route_yield` destination for `price`. Before the first
+    /// route the band collapses to `route_threshold_price`; afterwards the
+    /// last destination is kept until the price crosses the far side of the
+    /// band. With `conservative_routing`, a price whose confidence interval
+    /// straddles the bound keeps the last destination, or fails with
+    /// `RoutePriceAmbiguous` if there is none.
+    pub fn route_destination(&self, price: &NormalizedPrice) -> Result<RouteDestination> {
+        let (low, high) = if self.conservative_routing {
+            let conf =
+                i128::try_from(price.conf).map_err(|_| error!(UrbaniumError::MathOverflow))?;
+            (
+                price
+                    .price
+                    .checked_sub(conf)
+                    .ok_or_else(|| error!(UrbaniumError::MathOverflow))?,
+                price
+                    .price
+                    .checked_add(conf)
+                    .ok_or_else(|| error!(UrbaniumError::MathOverflow))?,
+            )
+        } else {
+            (price.price, price.price)
+        };
+
+        let (lower, upper) = match self.last_route_destination {
+            RouteDestination::Unset => (self.route_threshold_price, self.route_threshold_price),
+            _ => (self.route_lower_price, self.route_upper_price),
+        };
+
+        if low >= upper {
+            Ok(RouteDestination::YieldA)
+        } else if high < lower {
+            Ok(RouteDestination::YieldB)
+        } else if self.last_route_destination == RouteDestination::Unset {
+            err!(UrbaniumError::RoutePriceAmbiguous)
+        } else {
+            Ok(self.last_route_destination)
+        }
+    }
 }
 
+#[cfg(test)]
 impl Vault {
-    pub const VERSION: u8 = 1;
+    /// All-zero vault for unit tests; set the fields under test on top.
+    pub fn zeroed() -> Self {
+        Vault::deserialize(&mut &[0u8; Vault::LEN][..]).unwrap()
+    }
+}
 
-    pub const LEN: usize =
-        1 + 1 + 1 + 32 + 32 + 32 + 32 + 32 + 32 + 4 + 8 + 2 + 8 + 8;
+/// Yield sub-account chosen by `route_yield`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RouteDestination {
+    /// No route has happened yet.
+    #[default]
+    Unset,
+    YieldA,
+    YieldB,
+}
+
+/// Lifetime counters kept on the vault for dashboards. They are informational
+/// only; no pricing or accounting reads them.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct VaultStats {
+    /// Assets transferred in by deposits, entry fees included.
+    pub total_deposited: u128,
+    /// Assets paid out by withdrawals and claimed withdraw requests.
+    pub total_withdrawn: u128,
+    pub total_routed_to_a: u128,
+    pub total_routed_to_b: u128,
+    /// `UserPosition` accounts currently open, the fee position included.
+    pub open_positions: u64,
+    pub last_deposit_at: i64,
+    pub last_withdraw_at: i64,
+    pub last_route_at: i64,
+}
+
+impl VaultStats {
+    pub const LEN: usize = 16 + 16 + 16 + 16 + 8 + 8 + 8 + 8;
+
+    pub fn record_deposit(&mut self, assets: u64, now: i64) -> Result<()> {
+        self.total_deposited = checked_add(self.total_deposited, assets)?;
+        self.last_deposit_at = now;
+        Ok(())
+    }
+
+    pub fn record_withdraw(&mut self, assets: u64, now: i64) -> Result<()> {
+        self.total_withdrawn = checked_add(self.total_withdrawn, assets)?;
+        self.last_withdraw_at = now;
+        Ok(())
+    }
+
+    pub fn record_route(&mut self, to_yield_a: bool, amount: u64, now: i64) -> Result<()> {
+        if to_yield_a {
+            self.total_routed_to_a = checked_add(self.total_routed_to_a, amount)?;
+        } else {
+            self.total_routed_to_b = checked_add(self.total_routed_to_b, amount)?;
+        }
+        self.last_route_at = now;
+        Ok(())
+    }
+
+    pub fn position_opened(&mut self) -> Result<()> {
+        self.open_positions = self
+            .open_positions
+            .checked_add(1)
+            .ok_or_else(|| error!(UrbaniumError::MathOverflow))?;
+        Ok(())
+    }
+
+    pub fn position_closed(&mut self) {
+        self.open_positions = self.open_positions.saturating_sub(1);
+    }
+}
+
+fn checked_add(total: u128, amount: u64) -> Result<u128> {
+    total
+        .checked_add(u128::from(amount))
+        .ok_or_else(|| error!(UrbaniumError::MathOverflow))
 }
 
 #[account]
@@ -37,8 +331,395 @@ pub struct UserPosition {
     pub vault: Pubkey,
     pub owner: Pubkey,
     pub shares: u64,
+
+    /// May withdraw up to `delegated_shares` on the owner's behalf;
+    /// `Pubkey::default()` when no delegate is set.
+    pub delegate: Pubkey,
+    pub delegated_shares: u64,
+
+    /// Slot of the last deposit or incoming transfer credited to this
+    /// position.
+    pub last_deposit_slot: u64,
+    /// Shares credited within the vault's `min_hold_seconds`, which cannot
+    /// leave the position before `locked_until` without the early-exit
+    /// penalty. Stale once `locked_until` has passed.
+    pub locked_shares: u64,
+    pub locked_until: i64,
+
+    /// Assets paid into and out of `shares` over the position's life: entry
+    /// fees included in the former, exit fees excluded from the latter.
+    pub total_deposited: u128,
+    pub total_withdrawn: u128,
+    /// Weighted average cost of `shares` in assets per share, scaled by
+    /// `PRICE_SCALE`. Rounded up, so gains are never overstated. Reset by
+    /// `tokenize_position`.
+    pub avg_entry_price: u128,
+    /// Sum over redemptions of assets received minus the cost basis of the
+    /// shares given up.
+    pub realized_pnl: i128,
 }
 
 impl UserPosition {
-    pub const LEN: usize = 1 + 32 + 32 + 8;
+    pub const LEN: usize = 1 + 32 + 32 + 8 + 32 + 8 + 8 + 8 + 8 + 16 + 16 + 16 + 16;
+
+    /// Adds `shares` bought for `assets` to the cost basis. Call before
+    /// crediting the shares.
+    pub fn record_deposit(&mut self, shares: u64, assets: u64) -> Result<()> {
+        self.total_deposited = checked_add(self.total_deposited, assets)?;
+        self.add_cost_basis(shares, u128::from(assets) * PRICE_SCALE)
+    }
+
+    /// Adds `shares` acquired at `price` without a deposit (incoming
+    /// transfers, fee shares at zero cost). Call before crediting the shares.
+    pub fn record_acquired(&mut self, shares: u64, price: u128) -> Result<()> {
+        let cost = price
+            .checked_mul(u128::from(shares))
+            .ok_or_else(|| error!(UrbaniumError::MathOverflow))?;
+        self.add_cost_basis(shares, cost)
+    }
+
+    /// Locks `shares` credited at `now` for `hold_seconds`, together with
+    /// any shares still locked by earlier credits. Shares already past their
+    /// hold stay unlocked, so nobody can restart it on them for someone else.
+    /// Call before crediting the shares.
+    pub fn record_hold(
+        &mut self,
+        shares: u64,
+        hold_seconds: u64,
+        now: i64,
+        slot: u64,
+    ) -> Result<()> {
+        self.last_deposit_slot = slot;
+
+        if hold_seconds == 0 {
+            return Ok(());
+        }
+
+        self.locked_shares = self
+            .locked_shares_at(now)
+            .checked_add(shares)
+            .ok_or_else(|| error!(UrbaniumError::MathOverflow))?;
+        self.locked_until = now.saturating_add(i64::try_from(hold_seconds).unwrap_or(i64::MAX));
+        Ok(())
+    }
+
+    /// Shares still inside their holding period at `now`. Withdrawals and
+    /// transfers draw on unlocked shares first, so this never exceeds
+    /// `shares`.
+    pub fn locked_shares_at(&self, now: i64) -> u64 {
+        if now < self.locked_until {
+            core::cmp::min(self.locked_shares, self.shares)
+        } else {
+            0
+        }
+    }
+
+    /// Realizes the gain or loss on `shares` redeemed for `assets`.
+    pub fn record_withdraw(&mut self, shares: u64, assets: u64) -> Result<()> {
+        self.total_withdrawn = checked_add(self.total_withdrawn, assets)?;
+
+        let cost = self
+            .avg_entry_price
+            .checked_mul(u128::from(shares))
+            .ok_or_else(|| error!(UrbaniumError::MathOverflow))?
+            .div_ceil(PRICE_SCALE);
+        let cost = i128::try_from(cost).map_err(|_| error!(UrbaniumError::MathOverflow))?;
+
+        self.realized_pnl = self
+            .realized_pnl
+            .checked_add(i128::from(assets))
+            .and_then(|pnl| pnl.checked_sub(cost))
+            .ok_or_else(|| error!(UrbaniumError::MathOverflow))?;
+        Ok(())
+    }
+
+    /// Empties the position into share tokens, which carry no cost basis:
+    /// `avg_entry_price` resets, while `total_deposited` and `realized_pnl`
+    /// keep the history of the shares that left.
+    pub fn record_tokenized(&mut self) {
+        self.shares = 0;
+        self.avg_entry_price = 0;
+    }
+
+    /// Blends `shares` costing `scaled_cost` (assets times `PRICE_SCALE`)
+    /// into `avg_entry_price`.
+    fn add_cost_basis(&mut self, shares: u64, scaled_cost: u128) -> Result<()> {
+        let held = self
+            .shares
+            .checked_add(shares)
+            .ok_or_else(|| error!(UrbaniumError::MathOverflow))?;
+        if held == 0 {
+            return Ok(());
+        }
+
+        self.avg_entry_price = self
+            .avg_entry_price
+            .checked_mul(u128::from(self.shares))
+            .and_then(|basis| basis.checked_add(scaled_cost))
+            .ok_or_else(|| error!(UrbaniumError::MathOverflow))?
+            .div_ceil(u128::from(held));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+impl UserPosition {
+    /// All-zero position for unit tests; set the fields under test on top.
+    pub fn zeroed() -> Self {
+        UserPosition::deserialize(&mut &[0u8; UserPosition::LEN][..]).unwrap()
+    }
+}
+
+/// Withdrawal queued by `request_withdraw`: the shares are already burned and
+/// `assets` is fixed at the request-time price until `claim_withdraw`.
+#[account]
+pub struct WithdrawRequest {
+    pub bump: u8,
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    pub id: u64,
+    pub shares: u64,
+    pub assets: u64,
+    pub requested_at: i64,
+}
+
+impl WithdrawRequest {
+    pub const LEN: usize = 1 + 32 + 32 + 8 + 8 + 8 + 8;
+}
+
+/// Program-owned price account read by `OracleKind::Mock`, for localnet and
+/// program-test suites. Only compiled with the `test-oracle` feature.
+#[cfg(feature = "test-oracle")]
+#[account]
+pub struct MockPrice {
+    /// Only signer allowed to `set_mock_price`.
+    pub authority: Pubkey,
+    pub price: i64,
+    pub conf: u64,
+    pub expo: i32,
+    pub publish_time: i64,
+    pub ema_price: i64,
+    pub ema_conf: u64,
+}
+
+#[cfg(feature = "test-oracle")]
+impl MockPrice {
+    pub const LEN: usize = 32 + 8 + 8 + 4 + 8 + 8 + 8;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOLD: u64 = 60;
+
+    fn position(shares: u64) -> UserPosition {
+        let mut position = UserPosition::zeroed();
+        position.shares = shares;
+        position
+    }
+
+    /// Mirrors `settle_deposit` and `transfer_shares`: hold first, then credit.
+    fn credit(position: &mut UserPosition, shares: u64, now: i64) {
+        position.record_hold(shares, HOLD, now, 7).unwrap();
+        position.shares += shares;
+    }
+
+    #[test]
+    fn credit_locks_new_shares_for_the_hold() {
+        let mut position = position(0);
+        credit(&mut position, 100, 1_000);
+
+        assert_eq!(position.last_deposit_slot, 7);
+        assert_eq!(position.locked_until, 1_060);
+        assert_eq!(position.locked_shares_at(1_059), 100);
+        assert_eq!(position.locked_shares_at(1_060), 0);
+    }
+
+    #[test]
+    fn credit_cannot_lock_shares_past_their_hold() {
+        let mut position = position(0);
+        credit(&mut position, 1_000_000, 1_000);
+
+        // A third party crediting one share after the hold locks only that
+        // share.
+        credit(&mut position, 1, 2_000);
+        assert_eq!(position.locked_shares_at(2_000), 1);
+        assert_eq!(position.locked_until, 2_060);
+    }
+
+    #[test]
+    fn credit_inside_hold_extends_pending_lock() {
+        let mut position = position(0);
+        credit(&mut position, 100, 1_000);
+        credit(&mut position, 50, 1_030);
+
+        assert_eq!(position.locked_shares_at(1_060), 150);
+        assert_eq!(position.locked_shares_at(1_090), 0);
+    }
+
+    #[test]
+    fn locked_shares_never_exceed_held_shares() {
+        let mut position = position(0);
+        credit(&mut position, 100, 1_000);
+        position.shares = 30;
+
+        assert_eq!(position.locked_shares_at(1_000), 30);
+    }
+
+    #[test]
+    fn vault_without_hold_locks_nothing() {
+        let mut position = position(0);
+        position.record_hold(100, 0, 1_000, 7).unwrap();
+
+        assert_eq!(position.last_deposit_slot, 7);
+        assert_eq!(position.locked_shares_at(1_000), 0);
+    }
+
+    #[test]
+    fn deposits_blend_entry_price_rounded_up() {
+        let mut position = position(0);
+        position.record_deposit(100, 150).unwrap();
+        position.shares += 100;
+        assert_eq!(position.avg_entry_price, 3 * PRICE_SCALE / 2);
+
+        position.record_deposit(200, 100).unwrap();
+        position.shares += 200;
+        // 250 assets for 300 shares.
+        assert_eq!(position.avg_entry_price, 833_333_333_333_333_334);
+        assert_eq!(position.total_deposited, 250);
+    }
+
+    #[test]
+    fn acquired_shares_blend_at_their_price() {
+        let mut position = position(0);
+        position.record_acquired(100, 2 * PRICE_SCALE).unwrap();
+        position.shares += 100;
+        assert_eq!(position.avg_entry_price, 2 * PRICE_SCALE);
+
+        // Fee shares come in at zero cost.
+        position.record_acquired(300, 0).unwrap();
+        position.shares += 300;
+        assert_eq!(position.avg_entry_price, PRICE_SCALE / 2);
+        assert_eq!(position.total_deposited, 0);
+    }
+
+    #[test]
+    fn withdrawals_realize_pnl_against_entry_price() {
+        let mut position = position(0);
+        position.record_deposit(100, 100).unwrap();
+        position.shares += 100;
+
+        position.record_withdraw(40, 50).unwrap();
+        position.shares -= 40;
+        assert_eq!(position.realized_pnl, 10);
+
+        position.record_withdraw(60, 45).unwrap();
+        position.shares -= 60;
+        assert_eq!(position.realized_pnl, -5);
+        assert_eq!(position.total_withdrawn, 95);
+    }
+
+    #[test]
+    fn withdrawal_cost_rounds_up() {
+        let mut position = position(0);
+        position.record_deposit(3, 1).unwrap();
+        position.shares += 3;
+
+        // Each share cost a third of an asset; one share costs 1 after rounding.
+        position.record_withdraw(1, 1).unwrap();
+        assert_eq!(position.realized_pnl, 0);
+    }
+
+    #[test]
+    fn tokenizing_resets_cost_basis() {
+        let mut position = position(0);
+        position.record_deposit(100, 150).unwrap();
+        position.shares += 100;
+
+        position.record_tokenized();
+        assert_eq!(position.shares, 0);
+        assert_eq!(position.avg_entry_price, 0);
+        assert_eq!(position.total_deposited, 150);
+
+        position.record_deposit(100, 100).unwrap();
+        position.shares += 100;
+        assert_eq!(position.avg_entry_price, PRICE_SCALE);
+    }
+
+    /// Threshold 100 inside the hysteresis band [90, 110).
+    fn routing_vault(conservative_routing: bool, last: RouteDestination) -> Vault {
+        let mut vault = Vault::zeroed();
+        vault.route_threshold_price = 100;
+        vault.route_lower_price = 90;
+        vault.route_upper_price = 110;
+        vault.conservative_routing = conservative_routing;
+        vault.last_route_destination = last;
+        vault
+    }
+
+    fn route(vault: &Vault, price: i128, conf: u128) -> Result<RouteDestination> {
+        vault.route_destination(&NormalizedPrice {
+            price,
+            conf,
+            publish_time: 0,
+        })
+    }
+
+    #[test]
+    fn first_route_compares_against_threshold_only() {
+        let vault = routing_vault(false, RouteDestination::Unset);
+
+        assert_eq!(route(&vault, 100, 0).unwrap(), RouteDestination::YieldA);
+        assert_eq!(route(&vault, 99, 0).unwrap(), RouteDestination::YieldB);
+        assert_eq!(route(&vault, 105, 0).unwrap(), RouteDestination::YieldA);
+        assert_eq!(route(&vault, 95, 0).unwrap(), RouteDestination::YieldB);
+    }
+
+    #[test]
+    fn price_inside_band_keeps_last_destination() {
+        let vault = routing_vault(false, RouteDestination::YieldA);
+        assert_eq!(route(&vault, 95, 0).unwrap(), RouteDestination::YieldA);
+
+        let vault = routing_vault(false, RouteDestination::YieldB);
+        assert_eq!(route(&vault, 105, 0).unwrap(), RouteDestination::YieldB);
+    }
+
+    #[test]
+    fn crossing_band_edges_switches_destination() {
+        let vault = routing_vault(false, RouteDestination::YieldA);
+        assert_eq!(route(&vault, 90, 0).unwrap(), RouteDestination::YieldA);
+        assert_eq!(route(&vault, 89, 0).unwrap(), RouteDestination::YieldB);
+
+        let vault = routing_vault(false, RouteDestination::YieldB);
+        assert_eq!(route(&vault, 109, 0).unwrap(), RouteDestination::YieldB);
+        assert_eq!(route(&vault, 110, 0).unwrap(), RouteDestination::YieldA);
+    }
+
+    #[test]
+    fn confidence_ignored_without_conservative_routing() {
+        let vault = routing_vault(false, RouteDestination::Unset);
+
+        assert_eq!(route(&vault, 100, 50).unwrap(), RouteDestination::YieldA);
+    }
+
+    #[test]
+    fn conservative_routing_requires_whole_interval_past_bound() {
+        let vault = routing_vault(true, RouteDestination::Unset);
+        assert_eq!(
+            route(&vault, 100, 5).unwrap_err(),
+            error!(UrbaniumError::RoutePriceAmbiguous)
+        );
+        assert_eq!(route(&vault, 105, 5).unwrap(), RouteDestination::YieldA);
+        assert_eq!(route(&vault, 94, 5).unwrap(), RouteDestination::YieldB);
+
+        // A wide interval reaching past the far edge keeps the destination.
+        let vault = routing_vault(true, RouteDestination::YieldA);
+        assert_eq!(route(&vault, 85, 10).unwrap(), RouteDestination::YieldA);
+        assert_eq!(route(&vault, 84, 5).unwrap(), RouteDestination::YieldB);
+
+        let vault = routing_vault(true, RouteDestination::YieldB);
+        assert_eq!(route(&vault, 115, 10).unwrap(), RouteDestination::YieldB);
+        assert_eq!(route(&vault, 115, 5).unwrap(), RouteDestination::YieldA);
+    }
 }
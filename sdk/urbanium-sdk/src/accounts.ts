@@ -1,16 +1,39 @@
 import { BorshAccountsCoder, type IdlAccounts } from "@coral-xyz/anchor";
 
-import { urbaniumIdl, type UrbaniumIdl } from "./idl.js";
+import { VAULT_VERSION } from "./constants.js";
+import {
+  urbaniumIdl,
+  urbaniumTestOracleIdl,
+  type UrbaniumIdl,
+  type UrbaniumTestOracleIdl,
+} from "./idl.js";
 
 export type VaultAccount = IdlAccounts<UrbaniumIdl>["vault"];
 export type UserPositionAccount = IdlAccounts<UrbaniumIdl>["userPosition"];
+export type WithdrawRequestAccount = IdlAccounts<UrbaniumIdl>["withdrawRequest"];
+export type MockPriceAccount = IdlAccounts<UrbaniumTestOracleIdl>["mockPrice"];
 
 const coder = new BorshAccountsCoder(urbaniumIdl);
+const testOracleCoder = new BorshAccountsCoder(urbaniumTestOracleIdl);
 
+/** Throws on vaults of any version other than `VAULT_VERSION`. */
 export function decodeVault(data: Buffer): VaultAccount {
-  return coder.decode<VaultAccount>("vault", data);
+  const vault = coder.decode<VaultAccount>("vault", data);
+  if (vault.version !== VAULT_VERSION) {
+    throw new Error(`Unsupported vault version ${vault.version}, expected ${VAULT_VERSION}`);
+  }
+  return vault;
 }
 
 export function decodeUserPosition(data: Buffer): UserPositionAccount {
   return coder.decode<UserPositionAccount>("userPosition", data);
 }
+
+export function decodeWithdrawRequest(data: Buffer): WithdrawRequestAccount {
+  return coder.decode<WithdrawRequestAccount>("withdrawRequest", data);
+}
+
+/** Decodes a `MockPrice` account of a `test-oracle` build. */
+export function decodeMockPrice(data: Buffer): MockPriceAccount {
+  return testOracleCoder.decode<MockPriceAccount>("mockPrice", data);
+}
@@ -13,6 +13,7 @@ import {
 
 import { urbaniumIdl } from "./idl.js";
 import { parseAnchorCustomError, UrbaniumSdkError } from "./errors.js";
+import { decodeQuoteReturnData, decodeU64ReturnData, type Quote } from "./instructions.js";
 
 export type UrbaniumClientOptions = {
   connection: Connection;
@@ -72,4 +73,52 @@ export class UrbaniumClient {
       throw new UrbaniumSdkError("Transaction failed", e);
     }
   }
+
+  /**
+   * Simulates a view instruction (`buildConvertToSharesIx`, `buildMaxWithdrawIx`, ...)
+   * and decodes the `u64` it returns. Nothing is signed or sent.
+   */
+  async simulateU64(ix: TransactionInstruction): Promise<bigint> {
+    return decodeU64ReturnData(await this.simulateReturnData(ix));
+  }
+
+  /**
+   * Simulates `buildPreviewDepositIx` / `buildPreviewWithdrawIx` and decodes
+   * the amount and the entry/exit fee they return.
+   */
+  async simulateQuote(ix: TransactionInstruction): Promise<Quote> {
+    return decodeQuoteReturnData(await this.simulateReturnData(ix));
+  }
+
+  private async simulateReturnData(ix: TransactionInstruction): Promise<Buffer> {
+    const payer = this.wallet.publicKey;
+    if (!payer) throw new UrbaniumSdkError("Wallet has no publicKey");
+
+    const latest = await this.connection.getLatestBlockhash("confirmed");
+
+    const tx = new Transaction({
+      feePayer: payer,
+      blockhash: latest.blockhash,
+      lastValidBlockHeight: latest.lastValidBlockHeight,
+    }).add(ix);
+
+    const sim = await this.connection.simulateTransaction(tx);
+    if (sim.value.err) {
+      const parsed = parseAnchorCustomError({ logs: sim.value.logs }, this.idl);
+      if (parsed) {
+        throw new UrbaniumSdkError(
+          `Urbanium program error ${parsed.code}${parsed.name ? ` (${parsed.name})` : ""}${parsed.msg ? `: ${parsed.msg}` : ""}`,
+          sim.value.err,
+        );
+      }
+      throw new UrbaniumSdkError("Simulation failed", sim.value.err);
+    }
+
+    const returnData = sim.value.returnData;
+    if (!returnData || returnData.programId !== this.programId.toBase58()) {
+      throw new UrbaniumSdkError("Simulation returned no Urbanium return data");
+    }
+
+    return Buffer.from(returnData.data[0], "base64");
+  }
 }
@@ -1,3 +1,16 @@
 export const VAULT_SEED = "urbanium_vault" as const;
 export const VAULT_AUTHORITY_SEED = "urbanium_vault_authority" as const;
 export const USER_POSITION_SEED = "urbanium_user_position" as const;
+export const TOKEN_ACCOUNT_SEED = "urbanium_token_account" as const;
+export const SHARE_MINT_SEED = "urbanium_share_mint" as const;
+export const WITHDRAW_REQUEST_SEED = "urbanium_withdraw_request" as const;
+
+/** `Vault::VERSION`: the only vault layout version this SDK and the program accept. */
+export const VAULT_VERSION = 4 as const;
+
+/** Anchor `#[event_cpi]` authority seed. */
+export const EVENT_AUTHORITY_SEED = "__event_authority" as const;
+
+export const PRIMARY_TOKEN_ACCOUNT_ROLE = 0 as const;
+export const YIELD_A_TOKEN_ACCOUNT_ROLE = 1 as const;
+export const YIELD_B_TOKEN_ACCOUNT_ROLE = 2 as const;
@@ -0,0 +1,28 @@
+import { BorshEventCoder, type Idl } from "@coral-xyz/anchor";
+
+import { urbaniumIdl } from "./idl.js";
+
+/** `anchor_lang::event::EVENT_IX_TAG_LE`: prefix of self-CPI event instruction data. */
+export const EVENT_IX_TAG = Buffer.from([0xe4, 0x45, 0xa5, 0x2e, 0x51, 0xcb, 0x9a, 0x1d]);
+
+export type UrbaniumEvent = {
+  name: string;
+  data: Record<string, unknown>;
+};
+
+/**
+ * Decodes the data of an inner instruction emitted by `emit_cpi!`
+ * (`VaultInitialized`, `Deposited`, `Withdrawn`, `YieldRouted`,
+ * `WithdrawRequested`, `WithdrawClaimed`, `Donated`, `TotalAssetsSynced`,
+ * `SharesTransferred`, `FeesAccrued`, `DelegateApproved`, `DelegateRevoked`,
+ * `PositionClosed`, `PositionTokenized`).
+ * Returns `null` for any other instruction data.
+ */
+export function decodeUrbaniumEvent(ixData: Buffer, idl: Idl = urbaniumIdl): UrbaniumEvent | null {
+  if (ixData.length < EVENT_IX_TAG.length || !ixData.subarray(0, 8).equals(EVENT_IX_TAG)) {
+    return null;
+  }
+
+  const event = new BorshEventCoder(idl).decode(ixData.subarray(8).toString("base64"));
+  return event ? { name: event.name, data: event.data } : null;
+}
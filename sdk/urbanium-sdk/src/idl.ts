@@ -1,2 +1,4 @@
 export { urbaniumIdl } from "./urbaniumIdl.js";
 export type { UrbaniumIdl } from "./urbaniumIdl.js";
+export { urbaniumTestOracleIdl } from "./testOracleIdl.js";
+export type { UrbaniumTestOracleIdl } from "./testOracleIdl.js";
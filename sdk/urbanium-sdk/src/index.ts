@@ -3,6 +3,7 @@ export * from "./pdas.js";
 export * from "./idl.js";
 export * from "./accounts.js";
 export * from "./instructions.js";
+export * from "./events.js";
 export * from "./oracle.js";
 export * from "./errors.js";
 export * from "./client.js";
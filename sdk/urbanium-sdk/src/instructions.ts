@@ -6,7 +6,9 @@ import {
   TransactionInstruction,
 } from "@solana/web3.js";
 
-import { urbaniumIdl } from "./idl.js";
+import { urbaniumIdl, urbaniumTestOracleIdl } from "./idl.js";
+import type { OracleAggregation, OracleFeed, OracleKind, PriceSource } from "./oracle.js";
+import { deriveEventAuthorityPda } from "./pdas.js";
 
 function coder(idl: Idl = urbaniumIdl): BorshInstructionCoder {
   return new BorshInstructionCoder(idl);
@@ -21,12 +23,78 @@ function i64(x: bigint): BN {
   return new BN(x.toString(10), 10);
 }
 
+function i128(x: bigint): BN {
+  return new BN(x.toString(10), 10);
+}
+
+// Anchor encodes an omitted optional account as the program id.
+function optionalMeta(
+  programId: PublicKey,
+  pubkey: PublicKey | undefined,
+  isWritable: boolean,
+): AccountMeta {
+  return pubkey
+    ? { pubkey, isSigner: false, isWritable }
+    : { pubkey: programId, isSigner: false, isWritable: false };
+}
+
+// Trailing accounts added by `#[event_cpi]` to instructions that emit events.
+function eventCpiMetas(programId: PublicKey): AccountMeta[] {
+  const [eventAuthority] = deriveEventAuthorityPda(programId);
+  return [
+    { pubkey: eventAuthority, isSigner: false, isWritable: false },
+    { pubkey: programId, isSigner: false, isWritable: false },
+  ];
+}
+
+// Remaining accounts carrying `Vault.secondaryOracles`, in vault order.
+function oracleFeedMetas(feeds: PublicKey[] = []): AccountMeta[] {
+  return feeds.map((pubkey) => ({ pubkey, isSigner: false, isWritable: false }));
+}
+
 export type InitializeVaultArgs = {
   oracleProgram: PublicKey;
   oracleFeed: PublicKey;
   maxStalenessSeconds: bigint;
   maxConfidenceBps: number;
+  /** In `PRICE_DECIMALS` decimals; see `normalizePrice`. */
   routeThresholdPrice: bigint;
+  decimalsOffset: number;
+  feeRecipient: PublicKey;
+  /** Annualized management fee, at most 500. */
+  managementFeeBps: number;
+  /** Share of gains above the high-water mark, at most 5000. */
+  performanceFeeBps: number;
+  /** Vault-wide deposit cap in assets; `0n` disables it. */
+  maxTotalAssets: bigint;
+  /** Per-user deposit cap in assets; `0n` disables it. */
+  maxAssetsPerUser: bigint;
+  /** Entry fee kept by the vault, at most 1000. */
+  depositFeeBps: number;
+  /** Exit fee kept by the vault, at most 1000. */
+  withdrawFeeBps: number;
+  /** Holding period after a deposit; `0n` disables it. */
+  minHoldSeconds: bigint;
+  /** Extra exit fee inside the holding period, at most 1000; `0` rejects early exits instead. */
+  earlyExitPenaltyBps: number;
+  /** Account layout of `oracleFeed`. */
+  oracleKind: OracleKind;
+  /** Price `routeYield` compares against `routeThresholdPrice`. */
+  priceSource: PriceSource;
+  /** Extra feeds aggregated with the primary one, at most 4. */
+  secondaryOracles: OracleFeed[];
+  /** Feeds that must pass validation; `0` only without secondary feeds. */
+  oracleQuorum: number;
+  oracleAggregation: OracleAggregation;
+  /** Maximum deviation of any feed from the aggregate; `0` disables the check. */
+  maxOracleDivergenceBps: number;
+  /** Route on `price - conf` / `price + conf` instead of the price alone. */
+  conservativeRouting: boolean;
+  /** Hysteresis band containing `routeThresholdPrice`; set both to it to disable. */
+  routeLowerPrice: bigint;
+  routeUpperPrice: bigint;
+  /** Price feed id `oracleFeed` must carry when `oracleKind` is `pythPull`. */
+  oracleFeedId: Uint8Array;
 };
 
 export type InitializeVaultAccounts = {
@@ -37,10 +105,15 @@ export type InitializeVaultAccounts = {
   vaultTokenAccount: PublicKey;
   yieldTokenAccountA: PublicKey;
   yieldTokenAccountB: PublicKey;
+  /** Share mint PDA; omit to keep shares in `UserPosition` only. */
+  shareMint?: PublicKey;
+  /** Position PDA of `args.feeRecipient`; created here. */
+  feePosition: PublicKey;
   oracleFeed: PublicKey;
   systemProgram: PublicKey;
   tokenProgram: PublicKey;
-  associatedTokenProgram: PublicKey;
+  /** Feeds of `args.secondaryOracles`, in the same order. */
+  secondaryOracleFeeds?: PublicKey[];
 };
 
 export function buildInitializeVaultIx(
@@ -55,7 +128,32 @@ export function buildInitializeVaultIx(
       oracleFeed: args.oracleFeed,
       maxStalenessSeconds: u64(args.maxStalenessSeconds),
       maxConfidenceBps: args.maxConfidenceBps,
-      routeThresholdPrice: i64(args.routeThresholdPrice),
+      routeThresholdPrice: i128(args.routeThresholdPrice),
+      decimalsOffset: args.decimalsOffset,
+      feeRecipient: args.feeRecipient,
+      managementFeeBps: args.managementFeeBps,
+      performanceFeeBps: args.performanceFeeBps,
+      maxTotalAssets: u64(args.maxTotalAssets),
+      maxAssetsPerUser: u64(args.maxAssetsPerUser),
+      depositFeeBps: args.depositFeeBps,
+      withdrawFeeBps: args.withdrawFeeBps,
+      minHoldSeconds: u64(args.minHoldSeconds),
+      earlyExitPenaltyBps: args.earlyExitPenaltyBps,
+      oracleKind: { [args.oracleKind]: {} },
+      priceSource: { [args.priceSource]: {} },
+      secondaryOracles: args.secondaryOracles.map((o) => ({
+        kind: { [o.kind]: {} },
+        program: o.program,
+        feed: o.feed,
+        feedId: Array.from(o.feedId),
+      })),
+      oracleQuorum: args.oracleQuorum,
+      oracleAggregation: { [args.oracleAggregation]: {} },
+      maxOracleDivergenceBps: args.maxOracleDivergenceBps,
+      conservativeRouting: args.conservativeRouting,
+      routeLowerPrice: i128(args.routeLowerPrice),
+      routeUpperPrice: i128(args.routeUpperPrice),
+      oracleFeedId: Array.from(args.oracleFeedId),
     },
   });
 
@@ -67,10 +165,13 @@ export function buildInitializeVaultIx(
     { pubkey: accounts.vaultTokenAccount, isSigner: false, isWritable: true },
     { pubkey: accounts.yieldTokenAccountA, isSigner: false, isWritable: true },
     { pubkey: accounts.yieldTokenAccountB, isSigner: false, isWritable: true },
+    optionalMeta(programId, accounts.shareMint, true),
+    { pubkey: accounts.feePosition, isSigner: false, isWritable: true },
     { pubkey: accounts.oracleFeed, isSigner: false, isWritable: false },
     { pubkey: accounts.systemProgram, isSigner: false, isWritable: false },
     { pubkey: accounts.tokenProgram, isSigner: false, isWritable: false },
-    { pubkey: accounts.associatedTokenProgram, isSigner: false, isWritable: false },
+    ...eventCpiMetas(programId),
+    ...oracleFeedMetas(accounts.secondaryOracleFeeds),
   ];
 
   return new TransactionInstruction({ programId, keys, data });
@@ -78,14 +179,24 @@ export function buildInitializeVaultIx(
 
 export type DepositAccounts = {
   depositor: PublicKey;
+  /**
+   * Owner of the credited position; defaults to `depositor`.
+   * Only `deposit_for` accepts a different wallet.
+   */
+  beneficiary?: PublicKey;
   mint: PublicKey;
   vault: PublicKey;
   vaultAuthority: PublicKey;
-  userPosition: PublicKey;
+  /** Position PDA of `vault.feeRecipient`. */
+  feePosition: PublicKey;
+  /** Position PDA of the beneficiary; required unless the vault is tokenized, omitted if it is. */
+  userPosition?: PublicKey;
   userTokenAccount: PublicKey;
   vaultTokenAccount: PublicKey;
-  yieldTokenAccountA: PublicKey;
-  yieldTokenAccountB: PublicKey;
+  /** Required for tokenized vaults. */
+  shareMint?: PublicKey;
+  /** Required for tokenized vaults; receives the minted share tokens. */
+  userShareAccount?: PublicKey;
   systemProgram: PublicKey;
   tokenProgram: PublicKey;
   associatedTokenProgram: PublicKey;
@@ -95,38 +206,87 @@ export function buildDepositIx(
   programId: PublicKey,
   accounts: DepositAccounts,
   amount: bigint,
+  minSharesOut: bigint,
   idl: Idl = urbaniumIdl,
 ): TransactionInstruction {
-  const data = coder(idl).encode("deposit", { amount: u64(amount) });
+  const data = coder(idl).encode("deposit", {
+    amount: u64(amount),
+    minSharesOut: u64(minSharesOut),
+  });
 
-  const keys: AccountMeta[] = [
+  return new TransactionInstruction({ programId, keys: depositKeys(programId, accounts), data });
+}
+
+/** Like `buildDepositIx`, but credits `accounts.beneficiary` (and its position PDA). */
+export function buildDepositForIx(
+  programId: PublicKey,
+  accounts: DepositAccounts & { beneficiary: PublicKey },
+  amount: bigint,
+  minSharesOut: bigint,
+  idl: Idl = urbaniumIdl,
+): TransactionInstruction {
+  const data = coder(idl).encode("depositFor", {
+    amount: u64(amount),
+    minSharesOut: u64(minSharesOut),
+  });
+
+  return new TransactionInstruction({ programId, keys: depositKeys(programId, accounts), data });
+}
+
+export function buildMintSharesIx(
+  programId: PublicKey,
+  accounts: DepositAccounts,
+  shares: bigint,
+  maxAssetsIn: bigint,
+  idl: Idl = urbaniumIdl,
+): TransactionInstruction {
+  const data = coder(idl).encode("mintShares", {
+    shares: u64(shares),
+    maxAssetsIn: u64(maxAssetsIn),
+  });
+
+  return new TransactionInstruction({ programId, keys: depositKeys(programId, accounts), data });
+}
+
+function depositKeys(programId: PublicKey, accounts: DepositAccounts): AccountMeta[] {
+  return [
     { pubkey: accounts.depositor, isSigner: true, isWritable: true },
+    { pubkey: accounts.beneficiary ?? accounts.depositor, isSigner: false, isWritable: false },
     { pubkey: accounts.mint, isSigner: false, isWritable: false },
     { pubkey: accounts.vault, isSigner: false, isWritable: true },
     { pubkey: accounts.vaultAuthority, isSigner: false, isWritable: false },
-    { pubkey: accounts.userPosition, isSigner: false, isWritable: true },
+    { pubkey: accounts.feePosition, isSigner: false, isWritable: true },
+    optionalMeta(programId, accounts.userPosition, true),
     { pubkey: accounts.userTokenAccount, isSigner: false, isWritable: true },
     { pubkey: accounts.vaultTokenAccount, isSigner: false, isWritable: true },
-    { pubkey: accounts.yieldTokenAccountA, isSigner: false, isWritable: true },
-    { pubkey: accounts.yieldTokenAccountB, isSigner: false, isWritable: true },
+    optionalMeta(programId, accounts.shareMint, true),
+    optionalMeta(programId, accounts.userShareAccount, true),
     { pubkey: accounts.systemProgram, isSigner: false, isWritable: false },
     { pubkey: accounts.tokenProgram, isSigner: false, isWritable: false },
     { pubkey: accounts.associatedTokenProgram, isSigner: false, isWritable: false },
+    ...eventCpiMetas(programId),
   ];
-
-  return new TransactionInstruction({ programId, keys, data });
 }
 
 export type WithdrawAccounts = {
+  /** Position owner, or its delegate when withdrawing from `userPosition`. */
   withdrawer: PublicKey;
+  /** Position owner; receives the assets in `userTokenAccount`. */
+  owner: PublicKey;
   mint: PublicKey;
   vault: PublicKey;
   vaultAuthority: PublicKey;
-  userPosition: PublicKey;
+  /** Position PDA of `vault.feeRecipient`. */
+  feePosition: PublicKey;
+  /** Omit when burning share tokens from `userShareAccount`. */
+  userPosition?: PublicKey;
   userTokenAccount: PublicKey;
   vaultTokenAccount: PublicKey;
   yieldTokenAccountA: PublicKey;
   yieldTokenAccountB: PublicKey;
+  shareMint?: PublicKey;
+  /** When set, shares are burned from this account instead of `userPosition`. */
+  userShareAccount?: PublicKey;
   systemProgram: PublicKey;
   tokenProgram: PublicKey;
   associatedTokenProgram: PublicKey;
@@ -136,26 +296,54 @@ export function buildWithdrawIx(
   programId: PublicKey,
   accounts: WithdrawAccounts,
   shares: bigint,
+  minAmountOut: bigint,
+  closeIfEmpty = false,
   idl: Idl = urbaniumIdl,
 ): TransactionInstruction {
-  const data = coder(idl).encode("withdraw", { shares: u64(shares) });
+  const data = coder(idl).encode("withdraw", {
+    shares: u64(shares),
+    minAmountOut: u64(minAmountOut),
+    closeIfEmpty,
+  });
 
-  const keys: AccountMeta[] = [
+  return new TransactionInstruction({ programId, keys: withdrawKeys(programId, accounts), data });
+}
+
+export function buildWithdrawAssetsIx(
+  programId: PublicKey,
+  accounts: WithdrawAccounts,
+  assets: bigint,
+  maxSharesBurned: bigint,
+  idl: Idl = urbaniumIdl,
+): TransactionInstruction {
+  const data = coder(idl).encode("withdrawAssets", {
+    assets: u64(assets),
+    maxSharesBurned: u64(maxSharesBurned),
+  });
+
+  return new TransactionInstruction({ programId, keys: withdrawKeys(programId, accounts), data });
+}
+
+function withdrawKeys(programId: PublicKey, accounts: WithdrawAccounts): AccountMeta[] {
+  return [
     { pubkey: accounts.withdrawer, isSigner: true, isWritable: true },
+    { pubkey: accounts.owner, isSigner: false, isWritable: true },
     { pubkey: accounts.mint, isSigner: false, isWritable: false },
     { pubkey: accounts.vault, isSigner: false, isWritable: true },
     { pubkey: accounts.vaultAuthority, isSigner: false, isWritable: false },
-    { pubkey: accounts.userPosition, isSigner: false, isWritable: true },
+    { pubkey: accounts.feePosition, isSigner: false, isWritable: true },
+    optionalMeta(programId, accounts.userPosition, true),
     { pubkey: accounts.userTokenAccount, isSigner: false, isWritable: true },
     { pubkey: accounts.vaultTokenAccount, isSigner: false, isWritable: true },
     { pubkey: accounts.yieldTokenAccountA, isSigner: false, isWritable: true },
     { pubkey: accounts.yieldTokenAccountB, isSigner: false, isWritable: true },
+    optionalMeta(programId, accounts.shareMint, true),
+    optionalMeta(programId, accounts.userShareAccount, true),
     { pubkey: accounts.systemProgram, isSigner: false, isWritable: false },
     { pubkey: accounts.tokenProgram, isSigner: false, isWritable: false },
     { pubkey: accounts.associatedTokenProgram, isSigner: false, isWritable: false },
+    ...eventCpiMetas(programId),
   ];
-
-  return new TransactionInstruction({ programId, keys, data });
 }
 
 export type RouteYieldAccounts = {
@@ -163,11 +351,15 @@ export type RouteYieldAccounts = {
   mint: PublicKey;
   vault: PublicKey;
   vaultAuthority: PublicKey;
+  /** Position PDA of `vault.feeRecipient`. */
+  feePosition: PublicKey;
   vaultTokenAccount: PublicKey;
   yieldTokenAccountA: PublicKey;
   yieldTokenAccountB: PublicKey;
   oracleFeed: PublicKey;
   tokenProgram: PublicKey;
+  /** Feeds of `vault.secondaryOracles`, in the same order. */
+  secondaryOracleFeeds?: PublicKey[];
 };
 
 export function buildRouteYieldIx(
@@ -183,12 +375,525 @@ export function buildRouteYieldIx(
     { pubkey: accounts.mint, isSigner: false, isWritable: false },
     { pubkey: accounts.vault, isSigner: false, isWritable: true },
     { pubkey: accounts.vaultAuthority, isSigner: false, isWritable: false },
+    { pubkey: accounts.feePosition, isSigner: false, isWritable: true },
     { pubkey: accounts.vaultTokenAccount, isSigner: false, isWritable: true },
     { pubkey: accounts.yieldTokenAccountA, isSigner: false, isWritable: true },
     { pubkey: accounts.yieldTokenAccountB, isSigner: false, isWritable: true },
     { pubkey: accounts.oracleFeed, isSigner: false, isWritable: false },
     { pubkey: accounts.tokenProgram, isSigner: false, isWritable: false },
+    ...eventCpiMetas(programId),
+    ...oracleFeedMetas(accounts.secondaryOracleFeeds),
+  ];
+
+  return new TransactionInstruction({ programId, keys, data });
+}
+
+export type DonateAccounts = {
+  donor: PublicKey;
+  mint: PublicKey;
+  vault: PublicKey;
+  vaultAuthority: PublicKey;
+  donorTokenAccount: PublicKey;
+  vaultTokenAccount: PublicKey;
+  tokenProgram: PublicKey;
+  associatedTokenProgram: PublicKey;
+};
+
+export function buildDonateIx(
+  programId: PublicKey,
+  accounts: DonateAccounts,
+  amount: bigint,
+  idl: Idl = urbaniumIdl,
+): TransactionInstruction {
+  const data = coder(idl).encode("donate", { amount: u64(amount) });
+
+  const keys: AccountMeta[] = [
+    { pubkey: accounts.donor, isSigner: true, isWritable: false },
+    { pubkey: accounts.mint, isSigner: false, isWritable: false },
+    { pubkey: accounts.vault, isSigner: false, isWritable: true },
+    { pubkey: accounts.vaultAuthority, isSigner: false, isWritable: false },
+    { pubkey: accounts.donorTokenAccount, isSigner: false, isWritable: true },
+    { pubkey: accounts.vaultTokenAccount, isSigner: false, isWritable: true },
+    { pubkey: accounts.tokenProgram, isSigner: false, isWritable: false },
+    { pubkey: accounts.associatedTokenProgram, isSigner: false, isWritable: false },
+    ...eventCpiMetas(programId),
+  ];
+
+  return new TransactionInstruction({ programId, keys, data });
+}
+
+export type SyncTotalAssetsAccounts = {
+  mint: PublicKey;
+  vault: PublicKey;
+  vaultAuthority: PublicKey;
+  vaultTokenAccount: PublicKey;
+  yieldTokenAccountA: PublicKey;
+  yieldTokenAccountB: PublicKey;
+};
+
+export function buildSyncTotalAssetsIx(
+  programId: PublicKey,
+  accounts: SyncTotalAssetsAccounts,
+  idl: Idl = urbaniumIdl,
+): TransactionInstruction {
+  const data = coder(idl).encode("syncTotalAssets", {});
+
+  const keys: AccountMeta[] = [
+    { pubkey: accounts.mint, isSigner: false, isWritable: false },
+    { pubkey: accounts.vault, isSigner: false, isWritable: true },
+    { pubkey: accounts.vaultAuthority, isSigner: false, isWritable: false },
+    { pubkey: accounts.vaultTokenAccount, isSigner: false, isWritable: false },
+    { pubkey: accounts.yieldTokenAccountA, isSigner: false, isWritable: false },
+    { pubkey: accounts.yieldTokenAccountB, isSigner: false, isWritable: false },
+    ...eventCpiMetas(programId),
+  ];
+
+  return new TransactionInstruction({ programId, keys, data });
+}
+
+export type TokenizePositionAccounts = {
+  owner: PublicKey;
+  mint: PublicKey;
+  vault: PublicKey;
+  vaultAuthority: PublicKey;
+  userPosition: PublicKey;
+  shareMint: PublicKey;
+  userShareAccount: PublicKey;
+  tokenProgram: PublicKey;
+};
+
+export function buildTokenizePositionIx(
+  programId: PublicKey,
+  accounts: TokenizePositionAccounts,
+  idl: Idl = urbaniumIdl,
+): TransactionInstruction {
+  const data = coder(idl).encode("tokenizePosition", {});
+
+  const keys: AccountMeta[] = [
+    { pubkey: accounts.owner, isSigner: true, isWritable: false },
+    { pubkey: accounts.mint, isSigner: false, isWritable: false },
+    { pubkey: accounts.vault, isSigner: false, isWritable: false },
+    { pubkey: accounts.vaultAuthority, isSigner: false, isWritable: false },
+    { pubkey: accounts.userPosition, isSigner: false, isWritable: true },
+    { pubkey: accounts.shareMint, isSigner: false, isWritable: true },
+    { pubkey: accounts.userShareAccount, isSigner: false, isWritable: true },
+    { pubkey: accounts.tokenProgram, isSigner: false, isWritable: false },
+    ...eventCpiMetas(programId),
+  ];
+
+  return new TransactionInstruction({ programId, keys, data });
+}
+
+export type RequestWithdrawAccounts = {
+  owner: PublicKey;
+  mint: PublicKey;
+  vault: PublicKey;
+  /** Position PDA of `vault.feeRecipient`. */
+  feePosition: PublicKey;
+  /** Omit when burning share tokens from `userShareAccount`. */
+  userPosition?: PublicKey;
+  /** Request PDA for `vault.withdrawQueueTail`. */
+  withdrawRequest: PublicKey;
+  shareMint?: PublicKey;
+  /** When set, shares are burned from this account instead of `userPosition`. */
+  userShareAccount?: PublicKey;
+  systemProgram: PublicKey;
+  tokenProgram: PublicKey;
+};
+
+export function buildRequestWithdrawIx(
+  programId: PublicKey,
+  accounts: RequestWithdrawAccounts,
+  shares: bigint,
+  minAmountOut: bigint,
+  idl: Idl = urbaniumIdl,
+): TransactionInstruction {
+  const data = coder(idl).encode("requestWithdraw", {
+    shares: u64(shares),
+    minAmountOut: u64(minAmountOut),
+  });
+
+  const keys: AccountMeta[] = [
+    { pubkey: accounts.owner, isSigner: true, isWritable: true },
+    { pubkey: accounts.mint, isSigner: false, isWritable: false },
+    { pubkey: accounts.vault, isSigner: false, isWritable: true },
+    { pubkey: accounts.feePosition, isSigner: false, isWritable: true },
+    optionalMeta(programId, accounts.userPosition, true),
+    { pubkey: accounts.withdrawRequest, isSigner: false, isWritable: true },
+    optionalMeta(programId, accounts.shareMint, true),
+    optionalMeta(programId, accounts.userShareAccount, true),
+    { pubkey: accounts.systemProgram, isSigner: false, isWritable: false },
+    { pubkey: accounts.tokenProgram, isSigner: false, isWritable: false },
+    ...eventCpiMetas(programId),
+  ];
+
+  return new TransactionInstruction({ programId, keys, data });
+}
+
+export type ClaimWithdrawAccounts = {
+  /** Request owner; receives the assets and the request rent. */
+  owner: PublicKey;
+  mint: PublicKey;
+  vault: PublicKey;
+  vaultAuthority: PublicKey;
+  /** Request PDA for `vault.withdrawQueueHead`. */
+  withdrawRequest: PublicKey;
+  /** ATA of `owner` for `mint`. */
+  userTokenAccount: PublicKey;
+  vaultTokenAccount: PublicKey;
+  yieldTokenAccountA: PublicKey;
+  yieldTokenAccountB: PublicKey;
+  tokenProgram: PublicKey;
+};
+
+export function buildClaimWithdrawIx(
+  programId: PublicKey,
+  accounts: ClaimWithdrawAccounts,
+  idl: Idl = urbaniumIdl,
+): TransactionInstruction {
+  const data = coder(idl).encode("claimWithdraw", {});
+
+  const keys: AccountMeta[] = [
+    { pubkey: accounts.owner, isSigner: false, isWritable: true },
+    { pubkey: accounts.mint, isSigner: false, isWritable: false },
+    { pubkey: accounts.vault, isSigner: false, isWritable: true },
+    { pubkey: accounts.vaultAuthority, isSigner: false, isWritable: false },
+    { pubkey: accounts.withdrawRequest, isSigner: false, isWritable: true },
+    { pubkey: accounts.userTokenAccount, isSigner: false, isWritable: true },
+    { pubkey: accounts.vaultTokenAccount, isSigner: false, isWritable: true },
+    { pubkey: accounts.yieldTokenAccountA, isSigner: false, isWritable: true },
+    { pubkey: accounts.yieldTokenAccountB, isSigner: false, isWritable: true },
+    { pubkey: accounts.tokenProgram, isSigner: false, isWritable: false },
+    ...eventCpiMetas(programId),
+  ];
+
+  return new TransactionInstruction({ programId, keys, data });
+}
+
+export type ClosePositionAccounts = {
+  owner: PublicKey;
+  mint: PublicKey;
+  vault: PublicKey;
+  userPosition: PublicKey;
+};
+
+export function buildClosePositionIx(
+  programId: PublicKey,
+  accounts: ClosePositionAccounts,
+  idl: Idl = urbaniumIdl,
+): TransactionInstruction {
+  const data = coder(idl).encode("closePosition", {});
+
+  const keys: AccountMeta[] = [
+    { pubkey: accounts.owner, isSigner: true, isWritable: true },
+    { pubkey: accounts.mint, isSigner: false, isWritable: false },
+    { pubkey: accounts.vault, isSigner: false, isWritable: true },
+    { pubkey: accounts.userPosition, isSigner: false, isWritable: true },
+    ...eventCpiMetas(programId),
+  ];
+
+  return new TransactionInstruction({ programId, keys, data });
+}
+
+export type AccrueFeesAccounts = {
+  mint: PublicKey;
+  vault: PublicKey;
+  feePosition: PublicKey;
+};
+
+export function buildAccrueFeesIx(
+  programId: PublicKey,
+  accounts: AccrueFeesAccounts,
+  idl: Idl = urbaniumIdl,
+): TransactionInstruction {
+  const data = coder(idl).encode("accrueFees", {});
+
+  const keys: AccountMeta[] = [
+    { pubkey: accounts.mint, isSigner: false, isWritable: false },
+    { pubkey: accounts.vault, isSigner: false, isWritable: true },
+    { pubkey: accounts.feePosition, isSigner: false, isWritable: true },
+    ...eventCpiMetas(programId),
+  ];
+
+  return new TransactionInstruction({ programId, keys, data });
+}
+
+export type ManageDelegateAccounts = {
+  owner: PublicKey;
+  mint: PublicKey;
+  vault: PublicKey;
+  userPosition: PublicKey;
+};
+
+function manageDelegateKeys(
+  programId: PublicKey,
+  accounts: ManageDelegateAccounts,
+): AccountMeta[] {
+  return [
+    { pubkey: accounts.owner, isSigner: true, isWritable: false },
+    { pubkey: accounts.mint, isSigner: false, isWritable: false },
+    { pubkey: accounts.vault, isSigner: false, isWritable: false },
+    { pubkey: accounts.userPosition, isSigner: false, isWritable: true },
+    ...eventCpiMetas(programId),
+  ];
+}
+
+export function buildApproveDelegateIx(
+  programId: PublicKey,
+  accounts: ManageDelegateAccounts,
+  delegate: PublicKey,
+  shares: bigint,
+  idl: Idl = urbaniumIdl,
+): TransactionInstruction {
+  const data = coder(idl).encode("approveDelegate", { delegate, shares: u64(shares) });
+
+  const keys = manageDelegateKeys(programId, accounts);
+
+  return new TransactionInstruction({ programId, keys, data });
+}
+
+export function buildRevokeDelegateIx(
+  programId: PublicKey,
+  accounts: ManageDelegateAccounts,
+  idl: Idl = urbaniumIdl,
+): TransactionInstruction {
+  const data = coder(idl).encode("revokeDelegate", {});
+
+  const keys = manageDelegateKeys(programId, accounts);
+
+  return new TransactionInstruction({ programId, keys, data });
+}
+
+export type TransferSharesAccounts = {
+  owner: PublicKey;
+  recipient: PublicKey;
+  mint: PublicKey;
+  vault: PublicKey;
+  userPosition: PublicKey;
+  /** Position PDA of `recipient`; created by the instruction if missing. */
+  recipientPosition: PublicKey;
+  systemProgram: PublicKey;
+};
+
+export function buildTransferSharesIx(
+  programId: PublicKey,
+  accounts: TransferSharesAccounts,
+  amount: bigint,
+  idl: Idl = urbaniumIdl,
+): TransactionInstruction {
+  const data = coder(idl).encode("transferShares", { amount: u64(amount) });
+
+  const keys: AccountMeta[] = [
+    { pubkey: accounts.owner, isSigner: true, isWritable: true },
+    { pubkey: accounts.recipient, isSigner: false, isWritable: false },
+    { pubkey: accounts.mint, isSigner: false, isWritable: false },
+    { pubkey: accounts.vault, isSigner: false, isWritable: true },
+    { pubkey: accounts.userPosition, isSigner: false, isWritable: true },
+    { pubkey: accounts.recipientPosition, isSigner: false, isWritable: true },
+    { pubkey: accounts.systemProgram, isSigner: false, isWritable: false },
+    ...eventCpiMetas(programId),
+  ];
+
+  return new TransactionInstruction({ programId, keys, data });
+}
+
+export type InitMockPriceAccounts = {
+  authority: PublicKey;
+  /** Fresh keypair account; must also sign. */
+  mockPrice: PublicKey;
+  systemProgram: PublicKey;
+};
+
+/** Only exists in programs built with the `test-oracle` feature. */
+export function buildInitMockPriceIx(
+  programId: PublicKey,
+  accounts: InitMockPriceAccounts,
+  price: bigint,
+  conf: bigint,
+  expo: number,
+  idl: Idl = urbaniumTestOracleIdl,
+): TransactionInstruction {
+  const data = coder(idl).encode("initMockPrice", { price: i64(price), conf: u64(conf), expo });
+
+  const keys: AccountMeta[] = [
+    { pubkey: accounts.authority, isSigner: true, isWritable: true },
+    { pubkey: accounts.mockPrice, isSigner: true, isWritable: true },
+    { pubkey: accounts.systemProgram, isSigner: false, isWritable: false },
+  ];
+
+  return new TransactionInstruction({ programId, keys, data });
+}
+
+export type SetMockPriceAccounts = {
+  authority: PublicKey;
+  mockPrice: PublicKey;
+};
+
+/** Only exists in programs built with the `test-oracle` feature. */
+export function buildSetMockPriceIx(
+  programId: PublicKey,
+  accounts: SetMockPriceAccounts,
+  price: bigint,
+  conf: bigint,
+  expo: number,
+  publishTime: bigint,
+  emaPrice: bigint,
+  emaConf: bigint,
+  idl: Idl = urbaniumTestOracleIdl,
+): TransactionInstruction {
+  const data = coder(idl).encode("setMockPrice", {
+    price: i64(price),
+    conf: u64(conf),
+    expo,
+    publishTime: i64(publishTime),
+    emaPrice: i64(emaPrice),
+    emaConf: u64(emaConf),
+  });
+
+  const keys: AccountMeta[] = [
+    { pubkey: accounts.authority, isSigner: true, isWritable: false },
+    { pubkey: accounts.mockPrice, isSigner: false, isWritable: true },
+  ];
+
+  return new TransactionInstruction({ programId, keys, data });
+}
+
+export type PreviewAccounts = {
+  mint: PublicKey;
+  vault: PublicKey;
+};
+
+function buildPreviewIx(
+  programId: PublicKey,
+  accounts: PreviewAccounts,
+  data: Buffer,
+): TransactionInstruction {
+  const keys: AccountMeta[] = [
+    { pubkey: accounts.mint, isSigner: false, isWritable: false },
+    { pubkey: accounts.vault, isSigner: false, isWritable: false },
+  ];
+
+  return new TransactionInstruction({ programId, keys, data });
+}
+
+/** View: shares `deposit(amount)` would mint. Simulate and read the return data. */
+export function buildPreviewDepositIx(
+  programId: PublicKey,
+  accounts: PreviewAccounts,
+  amount: bigint,
+  idl: Idl = urbaniumIdl,
+): TransactionInstruction {
+  const data = coder(idl).encode("previewDeposit", { amount: u64(amount) });
+  return buildPreviewIx(programId, accounts, data);
+}
+
+/** View: assets `withdraw(shares)` would pay out. */
+export function buildPreviewWithdrawIx(
+  programId: PublicKey,
+  accounts: PreviewAccounts,
+  shares: bigint,
+  idl: Idl = urbaniumIdl,
+): TransactionInstruction {
+  const data = coder(idl).encode("previewWithdraw", { shares: u64(shares) });
+  return buildPreviewIx(programId, accounts, data);
+}
+
+export function buildConvertToSharesIx(
+  programId: PublicKey,
+  accounts: PreviewAccounts,
+  assets: bigint,
+  idl: Idl = urbaniumIdl,
+): TransactionInstruction {
+  const data = coder(idl).encode("convertToShares", { assets: u64(assets) });
+  return buildPreviewIx(programId, accounts, data);
+}
+
+export function buildConvertToAssetsIx(
+  programId: PublicKey,
+  accounts: PreviewAccounts,
+  shares: bigint,
+  idl: Idl = urbaniumIdl,
+): TransactionInstruction {
+  const data = coder(idl).encode("convertToAssets", { shares: u64(shares) });
+  return buildPreviewIx(programId, accounts, data);
+}
+
+export type MaxDepositAccounts = {
+  mint: PublicKey;
+  vault: PublicKey;
+  /** Position PDA of `owner`; omit when it does not exist yet. */
+  userPosition?: PublicKey;
+};
+
+/** View: largest deposit crediting `owner` the caps allow (`u64::MAX` when uncapped). */
+export function buildMaxDepositIx(
+  programId: PublicKey,
+  accounts: MaxDepositAccounts,
+  owner: PublicKey,
+  idl: Idl = urbaniumIdl,
+): TransactionInstruction {
+  const data = coder(idl).encode("maxDeposit", { owner });
+
+  const keys: AccountMeta[] = [
+    { pubkey: accounts.mint, isSigner: false, isWritable: false },
+    { pubkey: accounts.vault, isSigner: false, isWritable: false },
+    optionalMeta(programId, accounts.userPosition, false),
   ];
 
   return new TransactionInstruction({ programId, keys, data });
 }
+
+export type MaxWithdrawAccounts = {
+  mint: PublicKey;
+  vault: PublicKey;
+  vaultAuthority: PublicKey;
+  /** Position PDA of `owner`; omit when querying a share token balance. */
+  userPosition?: PublicKey;
+  /** When set, the share token balance is used instead of the position. */
+  userShareAccount?: PublicKey;
+  vaultTokenAccount: PublicKey;
+  yieldTokenAccountA: PublicKey;
+  yieldTokenAccountB: PublicKey;
+};
+
+/** View: largest amount `owner` can withdraw in one instruction. */
+export function buildMaxWithdrawIx(
+  programId: PublicKey,
+  accounts: MaxWithdrawAccounts,
+  owner: PublicKey,
+  idl: Idl = urbaniumIdl,
+): TransactionInstruction {
+  const data = coder(idl).encode("maxWithdraw", { owner });
+
+  const keys: AccountMeta[] = [
+    { pubkey: accounts.mint, isSigner: false, isWritable: false },
+    { pubkey: accounts.vault, isSigner: false, isWritable: false },
+    { pubkey: accounts.vaultAuthority, isSigner: false, isWritable: false },
+    optionalMeta(programId, accounts.userPosition, false),
+    optionalMeta(programId, accounts.userShareAccount, false),
+    { pubkey: accounts.vaultTokenAccount, isSigner: false, isWritable: false },
+    { pubkey: accounts.yieldTokenAccountA, isSigner: false, isWritable: false },
+    { pubkey: accounts.yieldTokenAccountB, isSigner: false, isWritable: false },
+  ];
+
+  return new TransactionInstruction({ programId, keys, data });
+}
+
+/** Decodes the little-endian `u64` a view instruction writes to return data. */
+export function decodeU64ReturnData(data: Buffer): bigint {
+  if (data.length !== 8) throw new Error(`expected 8 bytes of return data, got ${data.length}`);
+  return data.readBigUInt64LE(0);
+}
+
+/** Return value of `previewDeposit` / `previewWithdraw`. */
+export type Quote = {
+  /** Shares minted, or assets paid out, net of the fee. */
+  amount: bigint;
+  /** Entry or exit fee in assets, kept by the vault. */
+  fee: bigint;
+};
+
+export function decodeQuoteReturnData(data: Buffer): Quote {
+  if (data.length !== 16) throw new Error(`expected 16 bytes of return data, got ${data.length}`);
+  return { amount: data.readBigUInt64LE(0), fee: data.readBigUInt64LE(8) };
+}
@@ -1,23 +1,65 @@
 import { parsePriceData } from "@pythnetwork/client";
 import { Connection, PublicKey } from "@solana/web3.js";
 
-export type PythPrice = {
+import { decodeMockPrice } from "./accounts.js";
+
+export type OraclePrice = {
   price: bigint;
   conf: bigint;
   expo: number;
   publishTime: number;
+  /** EMA price and confidence, in the same exponent; absent for Switchboard. */
+  ema?: { price: bigint; conf: bigint };
+};
+
+/** @deprecated Use `OraclePrice`. */
+export type PythPrice = OraclePrice;
+
+/** Mirrors the program's `OracleKind`; stored in `Vault.oracleKind`. */
+export type OracleKind = "pythLegacy" | "pythPull" | "switchboardOnDemand" | "mock";
+
+/** Mirrors the program's `PriceSource`; stored in `Vault.priceSource`. */
+export type PriceSource = "spot" | "ema" | "min" | "max";
+
+/** Mirrors the program's `OracleAggregation`; stored in `Vault.oracleAggregation`. */
+export type OracleAggregation = "median" | "fallback";
+
+/**
+ * Mirrors the program's `OracleFeed`: one entry of `Vault.secondaryOracles`.
+ * `feedId` is the price feed id a `pythPull` feed must carry (see
+ * `decodePythPullFeedId`); other kinds ignore it.
+ */
+export type OracleFeed = {
+  kind: OracleKind;
+  program: PublicKey;
+  feed: PublicKey;
+  feedId: Uint8Array;
 };
 
+/** Decimals the program normalizes every oracle price to before comparing. */
+export const PRICE_DECIMALS = 18 as const;
+
+/** Decimals of Switchboard On-Demand fixed-point results. */
+export const SWITCHBOARD_DECIMALS = 18 as const;
+
+async function fetchOracleData(connection: Connection, account: PublicKey): Promise<Buffer> {
+  const info = await connection.getAccountInfo(account, {
+    commitment: "confirmed",
+  });
+  if (!info) throw new Error("Oracle account not found");
+
+  return info.data;
+}
+
 export async function readPythPrice(
   connection: Connection,
   priceAccount: PublicKey,
-): Promise<PythPrice> {
-  const info = await connection.getAccountInfo(priceAccount, {
-    commitment: "confirmed",
-  });
-  if (!info) throw new Error("Pyth price account not found");
+): Promise<OraclePrice> {
+  return decodePythLegacyPrice(await fetchOracleData(connection, priceAccount));
+}
 
-  const parsed = parsePriceData(info.data);
+export function decodePythLegacyPrice(data: Buffer): OraclePrice {
+  const parsed = parsePriceData(data);
   if (parsed.price === undefined) throw new Error("Pyth price unavailable");
   if (parsed.confidence === undefined) throw new Error("Pyth confidence unavailable");
 
@@ -29,5 +71,142 @@ export async function readPythPrice(
     conf,
     expo: parsed.exponent,
     publishTime: Number(parsed.timestamp),
+    ema: { price: parsed.emaPrice.valueComponent, conf: parsed.emaConfidence.valueComponent },
+  };
+}
+
+// discriminator (8) + write authority (32) + verification level (1)
+const PYTH_PULL_FEED_ID_OFFSET = 41;
+
+/** Price feed id of a Pyth pull `PriceUpdateV2` account. */
+export function decodePythPullFeedId(data: Buffer): Uint8Array {
+  return Uint8Array.from(data.subarray(PYTH_PULL_FEED_ID_OFFSET, PYTH_PULL_FEED_ID_OFFSET + 32));
+}
+
+/**
+ * Decodes a Pyth pull `PriceUpdateV2` account. Throws on partially verified
+ * updates, which the program rejects.
+ */
+export function decodePythPullPrice(data: Buffer): OraclePrice {
+  // discriminator (8) + write authority (32)
+  let offset = 40;
+  const verificationLevel = data.readUInt8(offset);
+  if (verificationLevel !== 1) throw new Error("Pyth price update not fully verified");
+  offset += 1;
+
+  // feed id
+  offset += 32;
+
+  return {
+    price: data.readBigInt64LE(offset),
+    conf: data.readBigUInt64LE(offset + 8),
+    expo: data.readInt32LE(offset + 16),
+    publishTime: Number(data.readBigInt64LE(offset + 20)),
+    // prev publish time (8) precedes the EMA.
+    ema: { price: data.readBigInt64LE(offset + 36), conf: data.readBigUInt64LE(offset + 44) },
   };
 }
+
+// Byte offsets into a Switchboard On-Demand `PullFeedAccountData`,
+// discriminator included.
+const SWITCHBOARD_RESULT_OFFSET = 2264;
+const SWITCHBOARD_SUBMISSION_TIMESTAMPS_OFFSET = 2952;
+const I64_MAX = 2n ** 63n - 1n;
+const U64_MAX = 2n ** 64n - 1n;
+
+function readI128LE(data: Buffer, offset: number): bigint {
+  const lo = data.readBigUInt64LE(offset);
+  const hi = data.readBigInt64LE(offset + 8);
+  return (hi << 64n) + lo;
+}
+
+/** Decodes a Switchboard On-Demand feed, rescaled like the program does. */
+export function decodeSwitchboardPrice(data: Buffer): OraclePrice {
+  const value = readI128LE(data, SWITCHBOARD_RESULT_OFFSET);
+  const stdDev = readI128LE(data, SWITCHBOARD_RESULT_OFFSET + 16);
+  const numSamples = data.readUInt8(SWITCHBOARD_RESULT_OFFSET + 96);
+  const submissionIdx = data.readUInt8(SWITCHBOARD_RESULT_OFFSET + 97);
+  if (numSamples === 0) throw new Error("Switchboard result unavailable");
+
+  // Keep the most decimals at which price and confidence fit the program's
+  // i64 / u64, truncating toward zero like it does.
+  let price = value;
+  let conf = stdDev < 0n ? -stdDev : stdDev;
+  let expo = -SWITCHBOARD_DECIMALS;
+  while (price > I64_MAX || price < -I64_MAX - 1n || conf > U64_MAX) {
+    price /= 10n;
+    conf /= 10n;
+    expo += 1;
+  }
+
+  return {
+    price,
+    conf,
+    expo,
+    publishTime: Number(
+      data.readBigInt64LE(SWITCHBOARD_SUBMISSION_TIMESTAMPS_OFFSET + 8 * submissionIdx),
+    ),
+  };
+}
+
+/** Decodes a `test-oracle` build's `MockPrice` account. */
+export function decodeMockOraclePrice(data: Buffer): OraclePrice {
+  const mock = decodeMockPrice(data);
+
+  return {
+    price: BigInt(mock.price.toString()),
+    conf: BigInt(mock.conf.toString()),
+    expo: mock.expo,
+    publishTime: mock.publishTime.toNumber(),
+    ema: { price: BigInt(mock.emaPrice.toString()), conf: BigInt(mock.emaConf.toString()) },
+  };
+}
+
+/**
+ * Off-chain mirror of `OraclePrice::select`: the price `route_yield` compares
+ * against the threshold for a vault with this `PriceSource`.
+ */
+export function selectOraclePrice(price: OraclePrice, source: PriceSource): OraclePrice {
+  const spot = { price: price.price, conf: price.conf };
+  let selected = spot;
+  if (source !== "spot") {
+    if (!price.ema) throw new Error("Oracle feed does not publish an EMA price");
+    const { ema } = price;
+    if (source === "ema") selected = ema;
+    if (source === "min" && ema.price < spot.price) selected = ema;
+    if (source === "max" && ema.price > spot.price) selected = ema;
+  }
+
+  return { ...selected, expo: price.expo, publishTime: price.publishTime };
+}
+
+/**
+ * Off-chain mirror of `oracle::normalize_price`: rescales `price * 10^expo` to
+ * `PRICE_DECIMALS` decimals, truncating toward zero. Use it to build
+ * `routeThresholdPrice` and to compare against `YieldRouted.oraclePrice`.
+ */
+export function normalizePrice(price: bigint, expo: number): bigint {
+  const shift = expo + PRICE_DECIMALS;
+  return shift >= 0 ? price * 10n ** BigInt(shift) : price / 10n ** BigInt(-shift);
+}
+
+export function decodeOraclePrice(kind: OracleKind, data: Buffer): OraclePrice {
+  switch (kind) {
+    case "pythLegacy":
+      return decodePythLegacyPrice(data);
+    case "pythPull":
+      return decodePythPullPrice(data);
+    case "switchboardOnDemand":
+      return decodeSwitchboardPrice(data);
+    case "mock":
+      return decodeMockOraclePrice(data);
+  }
+}
+
+export async function readOraclePrice(
+  connection: Connection,
+  kind: OracleKind,
+  oracleFeed: PublicKey,
+): Promise<OraclePrice> {
+  return decodeOraclePrice(kind, await fetchOracleData(connection, oracleFeed));
+}
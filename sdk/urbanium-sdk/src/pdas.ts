@@ -1,10 +1,26 @@
 import { PublicKey } from "@solana/web3.js";
 
-import { USER_POSITION_SEED, VAULT_AUTHORITY_SEED, VAULT_SEED } from "./constants.js";
+import {
+  EVENT_AUTHORITY_SEED,
+  PRIMARY_TOKEN_ACCOUNT_ROLE,
+  SHARE_MINT_SEED,
+  TOKEN_ACCOUNT_SEED,
+  USER_POSITION_SEED,
+  VAULT_AUTHORITY_SEED,
+  VAULT_SEED,
+  WITHDRAW_REQUEST_SEED,
+  YIELD_A_TOKEN_ACCOUNT_ROLE,
+  YIELD_B_TOKEN_ACCOUNT_ROLE,
+} from "./constants.js";
 
-export function deriveVaultPda(programId: PublicKey, mint: PublicKey): [PublicKey, number] {
+/** `creator` is the signer of `initializeVault` (its `payer` account). */
+export function deriveVaultPda(
+  programId: PublicKey,
+  mint: PublicKey,
+  creator: PublicKey,
+): [PublicKey, number] {
   return PublicKey.findProgramAddressSync(
-    [Buffer.from(VAULT_SEED, "utf8"), mint.toBuffer()],
+    [Buffer.from(VAULT_SEED, "utf8"), mint.toBuffer(), creator.toBuffer()],
     programId,
   );
 }
@@ -29,3 +45,60 @@ export function deriveUserPositionPda(
     programId,
   );
 }
+
+export function deriveShareMintPda(programId: PublicKey, vault: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from(SHARE_MINT_SEED, "utf8"), vault.toBuffer()],
+    programId,
+  );
+}
+
+export function deriveWithdrawRequestPda(
+  programId: PublicKey,
+  vault: PublicKey,
+  id: bigint,
+): [PublicKey, number] {
+  const idBytes = Buffer.alloc(8);
+  idBytes.writeBigUInt64LE(id);
+
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from(WITHDRAW_REQUEST_SEED, "utf8"), vault.toBuffer(), idBytes],
+    programId,
+  );
+}
+
+export function deriveTokenAccountPda(
+  programId: PublicKey,
+  vaultAuthority: PublicKey,
+  role: number,
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from(TOKEN_ACCOUNT_SEED, "utf8"), vaultAuthority.toBuffer(), Buffer.from([role])],
+    programId,
+  );
+}
+
+export function deriveVaultTokenAccountPda(
+  programId: PublicKey,
+  vaultAuthority: PublicKey,
+): [PublicKey, number] {
+  return deriveTokenAccountPda(programId, vaultAuthority, PRIMARY_TOKEN_ACCOUNT_ROLE);
+}
+
+export function deriveYieldTokenAccountAPda(
+  programId: PublicKey,
+  vaultAuthority: PublicKey,
+): [PublicKey, number] {
+  return deriveTokenAccountPda(programId, vaultAuthority, YIELD_A_TOKEN_ACCOUNT_ROLE);
+}
+
+export function deriveYieldTokenAccountBPda(
+  programId: PublicKey,
+  vaultAuthority: PublicKey,
+): [PublicKey, number] {
+  return deriveTokenAccountPda(programId, vaultAuthority, YIELD_B_TOKEN_ACCOUNT_ROLE);
+}
+
+export function deriveEventAuthorityPda(programId: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([Buffer.from(EVENT_AUTHORITY_SEED, "utf8")], programId);
+}
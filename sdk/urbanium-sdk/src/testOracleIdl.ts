@@ -0,0 +1,68 @@
+import type { Idl } from "@coral-xyz/anchor";
+
+import { urbaniumIdl } from "./urbaniumIdl.js";
+
+/**
+ * IDL of a program built with the `test-oracle` feature: the release IDL plus
+ * the mock price instructions and account, which release builds leave out.
+ */
+export const urbaniumTestOracleIdl = {
+  ...urbaniumIdl,
+  instructions: [
+    ...urbaniumIdl.instructions,
+    {
+      name: "initMockPrice",
+      discriminator: [86, 109, 119, 111, 238, 219, 127, 182],
+      accounts: [
+        { name: "authority", writable: true, signer: true },
+        { name: "mockPrice", writable: true, signer: true },
+        { name: "systemProgram" },
+      ],
+      args: [
+        { name: "price", type: "i64" },
+        { name: "conf", type: "u64" },
+        { name: "expo", type: "i32" },
+      ],
+    },
+    {
+      name: "setMockPrice",
+      discriminator: [161, 22, 71, 90, 159, 254, 26, 48],
+      accounts: [
+        { name: "authority", signer: true },
+        { name: "mockPrice", writable: true },
+      ],
+      args: [
+        { name: "price", type: "i64" },
+        { name: "conf", type: "u64" },
+        { name: "expo", type: "i32" },
+        { name: "publishTime", type: "i64" },
+        { name: "emaPrice", type: "i64" },
+        { name: "emaConf", type: "u64" },
+      ],
+    },
+  ],
+  accounts: [
+    ...urbaniumIdl.accounts,
+    { name: "mockPrice", discriminator: [2, 6, 235, 132, 88, 250, 211, 139] },
+  ],
+  types: [
+    ...urbaniumIdl.types,
+    {
+      name: "mockPrice",
+      type: {
+        kind: "struct",
+        fields: [
+          { name: "authority", type: "pubkey" },
+          { name: "price", type: "i64" },
+          { name: "conf", type: "u64" },
+          { name: "expo", type: "i32" },
+          { name: "publishTime", type: "i64" },
+          { name: "emaPrice", type: "i64" },
+          { name: "emaConf", type: "u64" },
+        ],
+      },
+    },
+  ],
+} as const satisfies Idl;
+
+export type UrbaniumTestOracleIdl = typeof urbaniumTestOracleIdl;
@@ -19,10 +19,13 @@ export const urbaniumIdl = {
         { name: "vaultTokenAccount", writable: true },
         { name: "yieldTokenAccountA", writable: true },
         { name: "yieldTokenAccountB", writable: true },
+        { name: "shareMint", writable: true, optional: true },
+        { name: "feePosition", writable: true },
         { name: "oracleFeed" },
         { name: "systemProgram" },
         { name: "tokenProgram" },
-        { name: "associatedTokenProgram" },
+        { name: "eventAuthority" },
+        { name: "program" },
       ],
       args: [
         {
@@ -36,38 +39,55 @@ export const urbaniumIdl = {
       discriminator: [242, 35, 198, 137, 82, 225, 242, 182],
       accounts: [
         { name: "depositor", writable: true, signer: true },
+        { name: "beneficiary" },
         { name: "mint" },
         { name: "vault", writable: true },
         { name: "vaultAuthority" },
-        { name: "userPosition", writable: true },
+        { name: "feePosition", writable: true },
+        { name: "userPosition", writable: true, optional: true },
         { name: "userTokenAccount", writable: true },
         { name: "vaultTokenAccount", writable: true },
-        { name: "yieldTokenAccountA", writable: true },
-        { name: "yieldTokenAccountB", writable: true },
+        { name: "shareMint", writable: true, optional: true },
+        { name: "userShareAccount", writable: true, optional: true },
         { name: "systemProgram" },
         { name: "tokenProgram" },
         { name: "associatedTokenProgram" },
+        { name: "eventAuthority" },
+        { name: "program" },
+      ],
+      args: [
+        { name: "amount", type: "u64" },
+        { name: "minSharesOut", type: "u64" },
       ],
-      args: [{ name: "amount", type: "u64" }],
     },
     {
       name: "withdraw",
       discriminator: [183, 18, 70, 156, 148, 109, 161, 34],
       accounts: [
         { name: "withdrawer", writable: true, signer: true },
+        { name: "owner", writable: true },
         { name: "mint" },
         { name: "vault", writable: true },
         { name: "vaultAuthority" },
-        { name: "userPosition", writable: true },
+        { name: "feePosition", writable: true },
+        { name: "userPosition", writable: true, optional: true },
         { name: "userTokenAccount", writable: true },
         { name: "vaultTokenAccount", writable: true },
         { name: "yieldTokenAccountA", writable: true },
         { name: "yieldTokenAccountB", writable: true },
+        { name: "shareMint", writable: true, optional: true },
+        { name: "userShareAccount", writable: true, optional: true },
         { name: "systemProgram" },
         { name: "tokenProgram" },
         { name: "associatedTokenProgram" },
+        { name: "eventAuthority" },
+        { name: "program" },
+      ],
+      args: [
+        { name: "shares", type: "u64" },
+        { name: "minAmountOut", type: "u64" },
+        { name: "closeIfEmpty", type: "bool" },
       ],
-      args: [{ name: "shares", type: "u64" }],
     },
     {
       name: "routeYield",
@@ -77,14 +97,312 @@ export const urbaniumIdl = {
         { name: "mint" },
         { name: "vault", writable: true },
         { name: "vaultAuthority" },
+        { name: "feePosition", writable: true },
         { name: "vaultTokenAccount", writable: true },
         { name: "yieldTokenAccountA", writable: true },
         { name: "yieldTokenAccountB", writable: true },
         { name: "oracleFeed" },
         { name: "tokenProgram" },
+        { name: "eventAuthority" },
+        { name: "program" },
+      ],
+      args: [{ name: "amount", type: "u64" }],
+    },
+    {
+      name: "donate",
+      discriminator: [121, 186, 218, 211, 73, 70, 196, 180],
+      accounts: [
+        { name: "donor", signer: true },
+        { name: "mint" },
+        { name: "vault", writable: true },
+        { name: "vaultAuthority" },
+        { name: "donorTokenAccount", writable: true },
+        { name: "vaultTokenAccount", writable: true },
+        { name: "tokenProgram" },
+        { name: "associatedTokenProgram" },
+        { name: "eventAuthority" },
+        { name: "program" },
+      ],
+      args: [{ name: "amount", type: "u64" }],
+    },
+    {
+      name: "syncTotalAssets",
+      discriminator: [15, 241, 56, 107, 230, 171, 241, 225],
+      accounts: [
+        { name: "mint" },
+        { name: "vault", writable: true },
+        { name: "vaultAuthority" },
+        { name: "vaultTokenAccount" },
+        { name: "yieldTokenAccountA" },
+        { name: "yieldTokenAccountB" },
+        { name: "eventAuthority" },
+        { name: "program" },
+      ],
+      args: [],
+    },
+    {
+      name: "mintShares",
+      discriminator: [24, 196, 132, 0, 183, 158, 216, 142],
+      accounts: [
+        { name: "depositor", writable: true, signer: true },
+        { name: "beneficiary" },
+        { name: "mint" },
+        { name: "vault", writable: true },
+        { name: "vaultAuthority" },
+        { name: "feePosition", writable: true },
+        { name: "userPosition", writable: true, optional: true },
+        { name: "userTokenAccount", writable: true },
+        { name: "vaultTokenAccount", writable: true },
+        { name: "shareMint", writable: true, optional: true },
+        { name: "userShareAccount", writable: true, optional: true },
+        { name: "systemProgram" },
+        { name: "tokenProgram" },
+        { name: "associatedTokenProgram" },
+        { name: "eventAuthority" },
+        { name: "program" },
+      ],
+      args: [
+        { name: "shares", type: "u64" },
+        { name: "maxAssetsIn", type: "u64" },
+      ],
+    },
+    {
+      name: "withdrawAssets",
+      discriminator: [202, 105, 54, 155, 56, 33, 207, 254],
+      accounts: [
+        { name: "withdrawer", writable: true, signer: true },
+        { name: "owner", writable: true },
+        { name: "mint" },
+        { name: "vault", writable: true },
+        { name: "vaultAuthority" },
+        { name: "feePosition", writable: true },
+        { name: "userPosition", writable: true, optional: true },
+        { name: "userTokenAccount", writable: true },
+        { name: "vaultTokenAccount", writable: true },
+        { name: "yieldTokenAccountA", writable: true },
+        { name: "yieldTokenAccountB", writable: true },
+        { name: "shareMint", writable: true, optional: true },
+        { name: "userShareAccount", writable: true, optional: true },
+        { name: "systemProgram" },
+        { name: "tokenProgram" },
+        { name: "associatedTokenProgram" },
+        { name: "eventAuthority" },
+        { name: "program" },
+      ],
+      args: [
+        { name: "assets", type: "u64" },
+        { name: "maxSharesBurned", type: "u64" },
+      ],
+    },
+    {
+      name: "tokenizePosition",
+      discriminator: [167, 82, 237, 13, 157, 135, 59, 138],
+      accounts: [
+        { name: "owner", signer: true },
+        { name: "mint" },
+        { name: "vault" },
+        { name: "vaultAuthority" },
+        { name: "userPosition", writable: true },
+        { name: "shareMint", writable: true },
+        { name: "userShareAccount", writable: true },
+        { name: "tokenProgram" },
+        { name: "eventAuthority" },
+        { name: "program" },
+      ],
+      args: [],
+    },
+    {
+      name: "previewDeposit",
+      discriminator: [16, 61, 8, 235, 146, 126, 80, 84],
+      accounts: [{ name: "mint" }, { name: "vault" }],
+      args: [{ name: "amount", type: "u64" }],
+      returns: { defined: { name: "Quote" } },
+    },
+    {
+      name: "previewWithdraw",
+      discriminator: [66, 3, 217, 38, 187, 176, 144, 135],
+      accounts: [{ name: "mint" }, { name: "vault" }],
+      args: [{ name: "shares", type: "u64" }],
+      returns: { defined: { name: "Quote" } },
+    },
+    {
+      name: "convertToShares",
+      discriminator: [105, 52, 27, 205, 69, 47, 239, 221],
+      accounts: [{ name: "mint" }, { name: "vault" }],
+      args: [{ name: "assets", type: "u64" }],
+      returns: "u64",
+    },
+    {
+      name: "convertToAssets",
+      discriminator: [229, 109, 118, 143, 110, 190, 39, 123],
+      accounts: [{ name: "mint" }, { name: "vault" }],
+      args: [{ name: "shares", type: "u64" }],
+      returns: "u64",
+    },
+    {
+      name: "maxWithdraw",
+      discriminator: [120, 205, 134, 47, 124, 123, 74, 119],
+      accounts: [
+        { name: "mint" },
+        { name: "vault" },
+        { name: "vaultAuthority" },
+        { name: "userPosition", optional: true },
+        { name: "userShareAccount", optional: true },
+        { name: "vaultTokenAccount" },
+        { name: "yieldTokenAccountA" },
+        { name: "yieldTokenAccountB" },
+      ],
+      args: [{ name: "owner", type: "pubkey" }],
+      returns: "u64",
+    },
+    {
+      name: "closePosition",
+      discriminator: [123, 134, 81, 0, 49, 68, 98, 98],
+      accounts: [
+        { name: "owner", writable: true, signer: true },
+        { name: "mint" },
+        { name: "vault", writable: true },
+        { name: "userPosition", writable: true },
+        { name: "eventAuthority" },
+        { name: "program" },
+      ],
+      args: [],
+    },
+    {
+      name: "transferShares",
+      discriminator: [23, 136, 140, 15, 181, 54, 120, 175],
+      accounts: [
+        { name: "owner", writable: true, signer: true },
+        { name: "recipient" },
+        { name: "mint" },
+        { name: "vault", writable: true },
+        { name: "userPosition", writable: true },
+        { name: "recipientPosition", writable: true },
+        { name: "systemProgram" },
+        { name: "eventAuthority" },
+        { name: "program" },
       ],
       args: [{ name: "amount", type: "u64" }],
     },
+    {
+      name: "approveDelegate",
+      discriminator: [68, 6, 248, 64, 195, 222, 182, 223],
+      accounts: [
+        { name: "owner", signer: true },
+        { name: "mint" },
+        { name: "vault" },
+        { name: "userPosition", writable: true },
+        { name: "eventAuthority" },
+        { name: "program" },
+      ],
+      args: [
+        { name: "delegate", type: "pubkey" },
+        { name: "shares", type: "u64" },
+      ],
+    },
+    {
+      name: "revokeDelegate",
+      discriminator: [142, 66, 98, 126, 102, 60, 92, 163],
+      accounts: [
+        { name: "owner", signer: true },
+        { name: "mint" },
+        { name: "vault" },
+        { name: "userPosition", writable: true },
+        { name: "eventAuthority" },
+        { name: "program" },
+      ],
+      args: [],
+    },
+    {
+      name: "depositFor",
+      discriminator: [193, 39, 228, 88, 160, 254, 92, 53],
+      accounts: [
+        { name: "depositor", writable: true, signer: true },
+        { name: "beneficiary" },
+        { name: "mint" },
+        { name: "vault", writable: true },
+        { name: "vaultAuthority" },
+        { name: "feePosition", writable: true },
+        { name: "userPosition", writable: true, optional: true },
+        { name: "userTokenAccount", writable: true },
+        { name: "vaultTokenAccount", writable: true },
+        { name: "shareMint", writable: true, optional: true },
+        { name: "userShareAccount", writable: true, optional: true },
+        { name: "systemProgram" },
+        { name: "tokenProgram" },
+        { name: "associatedTokenProgram" },
+        { name: "eventAuthority" },
+        { name: "program" },
+      ],
+      args: [
+        { name: "amount", type: "u64" },
+        { name: "minSharesOut", type: "u64" },
+      ],
+    },
+    {
+      name: "accrueFees",
+      discriminator: [136, 229, 178, 88, 250, 122, 35, 46],
+      accounts: [
+        { name: "mint" },
+        { name: "vault", writable: true },
+        { name: "feePosition", writable: true },
+        { name: "eventAuthority" },
+        { name: "program" },
+      ],
+      args: [],
+    },
+    {
+      name: "maxDeposit",
+      discriminator: [160, 113, 66, 36, 132, 127, 192, 252],
+      accounts: [
+        { name: "mint" },
+        { name: "vault" },
+        { name: "userPosition", optional: true },
+      ],
+      args: [{ name: "owner", type: "pubkey" }],
+      returns: "u64",
+    },
+    {
+      name: "requestWithdraw",
+      discriminator: [137, 95, 187, 96, 250, 138, 31, 182],
+      accounts: [
+        { name: "owner", writable: true, signer: true },
+        { name: "mint" },
+        { name: "vault", writable: true },
+        { name: "feePosition", writable: true },
+        { name: "userPosition", writable: true, optional: true },
+        { name: "withdrawRequest", writable: true },
+        { name: "shareMint", writable: true, optional: true },
+        { name: "userShareAccount", writable: true, optional: true },
+        { name: "systemProgram" },
+        { name: "tokenProgram" },
+        { name: "eventAuthority" },
+        { name: "program" },
+      ],
+      args: [
+        { name: "shares", type: "u64" },
+        { name: "minAmountOut", type: "u64" },
+      ],
+    },
+    {
+      name: "claimWithdraw",
+      discriminator: [232, 89, 154, 117, 16, 204, 182, 224],
+      accounts: [
+        { name: "owner", writable: true },
+        { name: "mint" },
+        { name: "vault", writable: true },
+        { name: "vaultAuthority" },
+        { name: "withdrawRequest", writable: true },
+        { name: "userTokenAccount", writable: true },
+        { name: "vaultTokenAccount", writable: true },
+        { name: "yieldTokenAccountA", writable: true },
+        { name: "yieldTokenAccountB", writable: true },
+        { name: "tokenProgram" },
+        { name: "eventAuthority" },
+        { name: "program" },
+      ],
+      args: [],
+    },
   ],
   accounts: [
     { name: "vault", discriminator: [211, 8, 232, 43, 2, 152, 117, 119] },
@@ -92,6 +410,56 @@ export const urbaniumIdl = {
       name: "userPosition",
       discriminator: [251, 248, 209, 245, 83, 234, 17, 27],
     },
+    {
+      name: "withdrawRequest",
+      discriminator: [186, 239, 174, 191, 189, 13, 47, 196],
+    },
+  ],
+  events: [
+    {
+      name: "VaultInitialized",
+      discriminator: [180, 43, 207, 2, 18, 71, 3, 75],
+    },
+    { name: "Deposited", discriminator: [111, 141, 26, 45, 161, 35, 100, 57] },
+    { name: "Withdrawn", discriminator: [20, 89, 223, 198, 194, 124, 219, 13] },
+    {
+      name: "YieldRouted",
+      discriminator: [10, 238, 211, 233, 196, 63, 203, 70],
+    },
+    {
+      name: "WithdrawRequested",
+      discriminator: [114, 16, 240, 206, 93, 128, 151, 39],
+    },
+    {
+      name: "WithdrawClaimed",
+      discriminator: [77, 130, 89, 38, 239, 172, 174, 85],
+    },
+    { name: "Donated", discriminator: [12, 83, 251, 183, 39, 211, 125, 44] },
+    {
+      name: "TotalAssetsSynced",
+      discriminator: [249, 77, 230, 75, 138, 104, 243, 216],
+    },
+    {
+      name: "SharesTransferred",
+      discriminator: [219, 222, 239, 232, 2, 70, 64, 200],
+    },
+    { name: "FeesAccrued", discriminator: [1, 151, 46, 93, 244, 90, 12, 191] },
+    {
+      name: "DelegateApproved",
+      discriminator: [180, 218, 19, 23, 104, 176, 112, 123],
+    },
+    {
+      name: "DelegateRevoked",
+      discriminator: [10, 200, 133, 29, 238, 207, 193, 124],
+    },
+    {
+      name: "PositionClosed",
+      discriminator: [157, 163, 227, 228, 13, 97, 138, 121],
+    },
+    {
+      name: "PositionTokenized",
+      discriminator: [145, 0, 221, 205, 179, 232, 102, 149],
+    },
   ],
   types: [
     {
@@ -102,17 +470,52 @@ export const urbaniumIdl = {
           { name: "version", type: "u8" },
           { name: "bump", type: "u8" },
           { name: "authorityBump", type: "u8" },
+          { name: "vaultTokenAccountBump", type: "u8" },
+          { name: "yieldTokenAccountABump", type: "u8" },
+          { name: "yieldTokenAccountBBump", type: "u8" },
+          { name: "shareMintBump", type: "u8" },
           { name: "mint", type: "pubkey" },
           { name: "vaultTokenAccount", type: "pubkey" },
           { name: "yieldTokenAccountA", type: "pubkey" },
           { name: "yieldTokenAccountB", type: "pubkey" },
+          { name: "shareMint", type: "pubkey" },
           { name: "oracleProgram", type: "pubkey" },
           { name: "oracleFeed", type: "pubkey" },
           { name: "oracleExpo", type: "i32" },
           { name: "maxStalenessSeconds", type: "u64" },
           { name: "maxConfidenceBps", type: "u16" },
-          { name: "routeThresholdPrice", type: "i64" },
+          { name: "routeThresholdPrice", type: "i128" },
+          { name: "totalAssets", type: "u64" },
           { name: "totalShares", type: "u64" },
+          { name: "decimalsOffset", type: "u8" },
+          { name: "feeRecipient", type: "pubkey" },
+          { name: "managementFeeBps", type: "u16" },
+          { name: "performanceFeeBps", type: "u16" },
+          { name: "highWaterMark", type: "u128" },
+          { name: "lastFeeAccrual", type: "i64" },
+          { name: "maxTotalAssets", type: "u64" },
+          { name: "maxAssetsPerUser", type: "u64" },
+          { name: "queuedAssets", type: "u64" },
+          { name: "withdrawQueueHead", type: "u64" },
+          { name: "withdrawQueueTail", type: "u64" },
+          { name: "depositFeeBps", type: "u16" },
+          { name: "withdrawFeeBps", type: "u16" },
+          { name: "minHoldSeconds", type: "u64" },
+          { name: "earlyExitPenaltyBps", type: "u16" },
+          { name: "stats", type: { defined: { name: "VaultStats" } } },
+          { name: "oracleKind", type: { defined: { name: "OracleKind" } } },
+          { name: "priceSource", type: { defined: { name: "PriceSource" } } },
+          { name: "secondaryOracles", type: { array: [{ defined: { name: "OracleFeed" } }, 4] } },
+          { name: "secondaryOracleCount", type: "u8" },
+          { name: "oracleQuorum", type: "u8" },
+          { name: "oracleAggregation", type: { defined: { name: "OracleAggregation" } } },
+          { name: "maxOracleDivergenceBps", type: "u16" },
+          { name: "conservativeRouting", type: "bool" },
+          { name: "routeLowerPrice", type: "i128" },
+          { name: "routeUpperPrice", type: "i128" },
+          { name: "lastRouteDestination", type: { defined: { name: "RouteDestination" } } },
+          { name: "oracleFeedId", type: { array: ["u8", 32] } },
+          { name: "creator", type: "pubkey" },
         ],
       },
     },
@@ -125,6 +528,30 @@ export const urbaniumIdl = {
           { name: "vault", type: "pubkey" },
           { name: "owner", type: "pubkey" },
           { name: "shares", type: "u64" },
+          { name: "delegate", type: "pubkey" },
+          { name: "delegatedShares", type: "u64" },
+          { name: "lastDepositSlot", type: "u64" },
+          { name: "lockedShares", type: "u64" },
+          { name: "lockedUntil", type: "i64" },
+          { name: "totalDeposited", type: "u128" },
+          { name: "totalWithdrawn", type: "u128" },
+          { name: "avgEntryPrice", type: "u128" },
+          { name: "realizedPnl", type: "i128" },
+        ],
+      },
+    },
+    {
+      name: "withdrawRequest",
+      type: {
+        kind: "struct",
+        fields: [
+          { name: "bump", type: "u8" },
+          { name: "vault", type: "pubkey" },
+          { name: "owner", type: "pubkey" },
+          { name: "id", type: "u64" },
+          { name: "shares", type: "u64" },
+          { name: "assets", type: "u64" },
+          { name: "requestedAt", type: "i64" },
         ],
       },
     },
@@ -137,10 +564,319 @@ export const urbaniumIdl = {
           { name: "oracleFeed", type: "pubkey" },
           { name: "maxStalenessSeconds", type: "u64" },
           { name: "maxConfidenceBps", type: "u16" },
-          { name: "routeThresholdPrice", type: "i64" },
+          { name: "routeThresholdPrice", type: "i128" },
+          { name: "decimalsOffset", type: "u8" },
+          { name: "feeRecipient", type: "pubkey" },
+          { name: "managementFeeBps", type: "u16" },
+          { name: "performanceFeeBps", type: "u16" },
+          { name: "maxTotalAssets", type: "u64" },
+          { name: "maxAssetsPerUser", type: "u64" },
+          { name: "depositFeeBps", type: "u16" },
+          { name: "withdrawFeeBps", type: "u16" },
+          { name: "minHoldSeconds", type: "u64" },
+          { name: "earlyExitPenaltyBps", type: "u16" },
+          { name: "oracleKind", type: { defined: { name: "OracleKind" } } },
+          { name: "priceSource", type: { defined: { name: "PriceSource" } } },
+          { name: "secondaryOracles", type: { vec: { defined: { name: "OracleFeed" } } } },
+          { name: "oracleQuorum", type: "u8" },
+          { name: "oracleAggregation", type: { defined: { name: "OracleAggregation" } } },
+          { name: "maxOracleDivergenceBps", type: "u16" },
+          { name: "conservativeRouting", type: "bool" },
+          { name: "routeLowerPrice", type: "i128" },
+          { name: "routeUpperPrice", type: "i128" },
+          { name: "oracleFeedId", type: { array: ["u8", 32] } },
+        ],
+      },
+    },
+    {
+      name: "Quote",
+      type: {
+        kind: "struct",
+        fields: [
+          { name: "amount", type: "u64" },
+          { name: "fee", type: "u64" },
+        ],
+      },
+    },
+    {
+      name: "VaultInitialized",
+      type: {
+        kind: "struct",
+        fields: [
+          { name: "vault", type: "pubkey" },
+          { name: "mint", type: "pubkey" },
+          { name: "creator", type: "pubkey" },
+          { name: "shareMint", type: "pubkey" },
+          { name: "oracleProgram", type: "pubkey" },
+          { name: "oracleFeed", type: "pubkey" },
+          { name: "oracleKind", type: { defined: { name: "OracleKind" } } },
+          { name: "priceSource", type: { defined: { name: "PriceSource" } } },
+          { name: "oracleExpo", type: "i32" },
+          { name: "routeThresholdPrice", type: "i128" },
+          { name: "routeLowerPrice", type: "i128" },
+          { name: "routeUpperPrice", type: "i128" },
+          { name: "conservativeRouting", type: "bool" },
+          { name: "decimalsOffset", type: "u8" },
+          { name: "feeRecipient", type: "pubkey" },
+        ],
+      },
+    },
+    {
+      name: "Deposited",
+      type: {
+        kind: "struct",
+        fields: [
+          { name: "vault", type: "pubkey" },
+          { name: "depositor", type: "pubkey" },
+          { name: "owner", type: "pubkey" },
+          { name: "assets", type: "u64" },
+          { name: "shares", type: "u64" },
+          { name: "fee", type: "u64" },
+          { name: "tokenized", type: "bool" },
+          { name: "preTotalAssets", type: "u64" },
+          { name: "preTotalShares", type: "u64" },
+          { name: "postTotalAssets", type: "u64" },
+          { name: "postTotalShares", type: "u64" },
+        ],
+      },
+    },
+    {
+      name: "Withdrawn",
+      type: {
+        kind: "struct",
+        fields: [
+          { name: "vault", type: "pubkey" },
+          { name: "withdrawer", type: "pubkey" },
+          { name: "owner", type: "pubkey" },
+          { name: "shares", type: "u64" },
+          { name: "assets", type: "u64" },
+          { name: "fee", type: "u64" },
+          { name: "fromVaultTokenAccount", type: "u64" },
+          { name: "fromYieldTokenAccountA", type: "u64" },
+          { name: "fromYieldTokenAccountB", type: "u64" },
+          { name: "preTotalAssets", type: "u64" },
+          { name: "preTotalShares", type: "u64" },
+          { name: "postTotalAssets", type: "u64" },
+          { name: "postTotalShares", type: "u64" },
+        ],
+      },
+    },
+    {
+      name: "YieldRouted",
+      type: {
+        kind: "struct",
+        fields: [
+          { name: "vault", type: "pubkey" },
+          { name: "amount", type: "u64" },
+          { name: "destination", type: "pubkey" },
+          { name: "priceSource", type: { defined: { name: "PriceSource" } } },
+          { name: "oraclePrice", type: "i128" },
+          { name: "oracleConf", type: "u128" },
+          { name: "oraclePublishTime", type: "i64" },
+          { name: "routeThresholdPrice", type: "i128" },
+          { name: "preTotalAssets", type: "u64" },
+          { name: "preTotalShares", type: "u64" },
+          { name: "postTotalAssets", type: "u64" },
+          { name: "postTotalShares", type: "u64" },
+        ],
+      },
+    },
+    {
+      name: "WithdrawRequested",
+      type: {
+        kind: "struct",
+        fields: [
+          { name: "vault", type: "pubkey" },
+          { name: "owner", type: "pubkey" },
+          { name: "id", type: "u64" },
+          { name: "shares", type: "u64" },
+          { name: "assets", type: "u64" },
+          { name: "fee", type: "u64" },
+          { name: "tokenized", type: "bool" },
+          { name: "preTotalAssets", type: "u64" },
+          { name: "preTotalShares", type: "u64" },
+          { name: "postTotalAssets", type: "u64" },
+          { name: "postTotalShares", type: "u64" },
+        ],
+      },
+    },
+    {
+      name: "WithdrawClaimed",
+      type: {
+        kind: "struct",
+        fields: [
+          { name: "vault", type: "pubkey" },
+          { name: "owner", type: "pubkey" },
+          { name: "id", type: "u64" },
+          { name: "assets", type: "u64" },
+          { name: "fromVaultTokenAccount", type: "u64" },
+          { name: "fromYieldTokenAccountA", type: "u64" },
+          { name: "fromYieldTokenAccountB", type: "u64" },
+        ],
+      },
+    },
+    {
+      name: "Donated",
+      type: {
+        kind: "struct",
+        fields: [
+          { name: "vault", type: "pubkey" },
+          { name: "donor", type: "pubkey" },
+          { name: "amount", type: "u64" },
+          { name: "preTotalAssets", type: "u64" },
+          { name: "postTotalAssets", type: "u64" },
+        ],
+      },
+    },
+    {
+      name: "TotalAssetsSynced",
+      type: {
+        kind: "struct",
+        fields: [
+          { name: "vault", type: "pubkey" },
+          { name: "balance", type: "u64" },
+          { name: "preTotalAssets", type: "u64" },
+          { name: "postTotalAssets", type: "u64" },
+        ],
+      },
+    },
+    {
+      name: "SharesTransferred",
+      type: {
+        kind: "struct",
+        fields: [
+          { name: "vault", type: "pubkey" },
+          { name: "owner", type: "pubkey" },
+          { name: "recipient", type: "pubkey" },
+          { name: "shares", type: "u64" },
+          { name: "entryPrice", type: "u128" },
+        ],
+      },
+    },
+    {
+      name: "FeesAccrued",
+      type: {
+        kind: "struct",
+        fields: [
+          { name: "vault", type: "pubkey" },
+          { name: "feeRecipient", type: "pubkey" },
+          { name: "feeShares", type: "u64" },
+          { name: "managementFee", type: "u64" },
+          { name: "performanceFee", type: "u64" },
+          { name: "highWaterMark", type: "u128" },
+        ],
+      },
+    },
+    {
+      name: "DelegateApproved",
+      type: {
+        kind: "struct",
+        fields: [
+          { name: "vault", type: "pubkey" },
+          { name: "owner", type: "pubkey" },
+          { name: "delegate", type: "pubkey" },
+          { name: "shares", type: "u64" },
         ],
       },
     },
+    {
+      name: "DelegateRevoked",
+      type: {
+        kind: "struct",
+        fields: [
+          { name: "vault", type: "pubkey" },
+          { name: "owner", type: "pubkey" },
+          { name: "delegate", type: "pubkey" },
+        ],
+      },
+    },
+    {
+      name: "PositionClosed",
+      type: {
+        kind: "struct",
+        fields: [
+          { name: "vault", type: "pubkey" },
+          { name: "owner", type: "pubkey" },
+        ],
+      },
+    },
+    {
+      name: "PositionTokenized",
+      type: {
+        kind: "struct",
+        fields: [
+          { name: "vault", type: "pubkey" },
+          { name: "owner", type: "pubkey" },
+          { name: "shares", type: "u64" },
+        ],
+      },
+    },
+    {
+      name: "VaultStats",
+      type: {
+        kind: "struct",
+        fields: [
+          { name: "totalDeposited", type: "u128" },
+          { name: "totalWithdrawn", type: "u128" },
+          { name: "totalRoutedToA", type: "u128" },
+          { name: "totalRoutedToB", type: "u128" },
+          { name: "openPositions", type: "u64" },
+          { name: "lastDepositAt", type: "i64" },
+          { name: "lastWithdrawAt", type: "i64" },
+          { name: "lastRouteAt", type: "i64" },
+        ],
+      },
+    },
+    {
+      name: "OracleKind",
+      type: {
+        kind: "enum",
+        variants: [
+          { name: "PythLegacy" },
+          { name: "PythPull" },
+          { name: "SwitchboardOnDemand" },
+          { name: "Mock" },
+        ],
+      },
+    },
+    {
+      name: "PriceSource",
+      type: {
+        kind: "enum",
+        variants: [
+          { name: "Spot" },
+          { name: "Ema" },
+          { name: "Min" },
+          { name: "Max" },
+        ],
+      },
+    },
+    {
+      name: "OracleFeed",
+      type: {
+        kind: "struct",
+        fields: [
+          { name: "kind", type: { defined: { name: "OracleKind" } } },
+          { name: "program", type: "pubkey" },
+          { name: "feed", type: "pubkey" },
+          { name: "feedId", type: { array: ["u8", 32] } },
+        ],
+      },
+    },
+    {
+      name: "OracleAggregation",
+      type: {
+        kind: "enum",
+        variants: [{ name: "Median" }, { name: "Fallback" }],
+      },
+    },
+    {
+      name: "RouteDestination",
+      type: {
+        kind: "enum",
+        variants: [{ name: "Unset" }, { name: "YieldA" }, { name: "YieldB" }],
+      },
+    },
   ],
   errors: [
     { code: 6000, name: "InvalidVaultPda", msg: "Invalid vault PDA" },
@@ -200,6 +936,165 @@ export const urbaniumIdl = {
     },
     { code: 6015, name: "ZeroAmount", msg: "Deposit amount must be non-zero" },
     { code: 6016, name: "ZeroShares", msg: "Shares must be non-zero" },
+    {
+      code: 6017,
+      name: "ZeroSharesMinted",
+      msg: "Deposit amount too small to mint any shares",
+    },
+    {
+      code: 6018,
+      name: "InvalidDecimalsOffset",
+      msg: "Decimals offset exceeds maximum",
+    },
+    {
+      code: 6019,
+      name: "SharesBelowMinimum",
+      msg: "Shares minted below minimum",
+    },
+    {
+      code: 6020,
+      name: "AmountBelowMinimum",
+      msg: "Withdraw amount below minimum",
+    },
+    {
+      code: 6021,
+      name: "AssetsAboveMaximum",
+      msg: "Assets required exceed maximum",
+    },
+    {
+      code: 6022,
+      name: "SharesAboveMaximum",
+      msg: "Shares burned exceed maximum",
+    },
+    { code: 6023, name: "ShareMintNotEnabled", msg: "Vault has no share mint" },
+    { code: 6024, name: "InvalidShareMint", msg: "Invalid share mint" },
+    {
+      code: 6025,
+      name: "MissingShareAccount",
+      msg: "Share token account or user position required",
+    },
+    {
+      code: 6026,
+      name: "InvalidShareAccountOwner",
+      msg: "Share token account not owned by the position owner",
+    },
+    {
+      code: 6027,
+      name: "PositionNotEmpty",
+      msg: "User position still holds shares",
+    },
+    {
+      code: 6028,
+      name: "SelfShareTransfer",
+      msg: "Cannot transfer shares to the same position",
+    },
+    { code: 6029, name: "InvalidDelegate", msg: "Invalid delegate" },
+    {
+      code: 6030,
+      name: "UnauthorizedWithdrawer",
+      msg: "Signer is neither the position owner nor its delegate",
+    },
+    {
+      code: 6031,
+      name: "DelegateAllowanceExceeded",
+      msg: "Delegate allowance exceeded",
+    },
+    {
+      code: 6032,
+      name: "InvalidBeneficiary",
+      msg: "Beneficiary must be the depositor",
+    },
+    { code: 6033, name: "InvalidFeeBps", msg: "Fee exceeds maximum" },
+    {
+      code: 6034,
+      name: "FeePositionNotClosable",
+      msg: "Fee recipient position cannot be closed",
+    },
+    {
+      code: 6035,
+      name: "VaultDepositCapExceeded",
+      msg: "Deposit exceeds vault total assets cap",
+    },
+    {
+      code: 6036,
+      name: "UserDepositCapExceeded",
+      msg: "Deposit exceeds per-user assets cap",
+    },
+    {
+      code: 6037,
+      name: "WithdrawRequestNotNext",
+      msg: "Withdraw request is not at the head of the queue",
+    },
+    {
+      code: 6038,
+      name: "MinHoldPeriodActive",
+      msg: "Withdrawal within the minimum holding period",
+    },
+    {
+      code: 6039,
+      name: "MockOracleDisabled",
+      msg: "Mock oracle requires a test-oracle build",
+    },
+    {
+      code: 6040,
+      name: "OracleEmaUnavailable",
+      msg: "Oracle feed does not publish an EMA price",
+    },
+    {
+      code: 6041,
+      name: "InvalidOracleConfig",
+      msg: "Too many secondary oracles or invalid oracle quorum",
+    },
+    {
+      code: 6042,
+      name: "InvalidOracleFeed",
+      msg: "Oracle feed account missing or out of order",
+    },
+    {
+      code: 6043,
+      name: "OracleQuorumNotMet",
+      msg: "Too few oracle feeds passed validation",
+    },
+    {
+      code: 6044,
+      name: "OracleDivergenceTooHigh",
+      msg: "Oracle feeds diverge beyond the configured bound",
+    },
+    {
+      code: 6045,
+      name: "RoutePriceAmbiguous",
+      msg: "Oracle confidence interval straddles the routing threshold",
+    },
+    {
+      code: 6046,
+      name: "InvalidRouteBand",
+      msg: "Routing band must contain the routing threshold",
+    },
+    {
+      code: 6047,
+      name: "MinHoldWithShareMint",
+      msg: "Minimum holding period cannot be enforced on tokenized shares",
+    },
+    {
+      code: 6048,
+      name: "MissingUserPosition",
+      msg: "User position required",
+    },
+    {
+      code: 6049,
+      name: "UserCapWithShareMint",
+      msg: "Per-user deposit cap cannot be enforced on tokenized shares",
+    },
+    {
+      code: 6050,
+      name: "UnsupportedVaultVersion",
+      msg: "Vault account version is not supported by this program",
+    },
+    {
+      code: 6051,
+      name: "UserPositionWithShareMint",
+      msg: "Tokenized vaults mint share tokens instead of crediting a user position",
+    },
   ],
 } as const satisfies Idl;
 
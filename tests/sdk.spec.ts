@@ -3,13 +3,40 @@ import { describe, it } from "mocha";
 import { PublicKey, SystemProgram } from "@solana/web3.js";
 
 import {
+  buildAccrueFeesIx,
+  buildApproveDelegateIx,
+  buildClaimWithdrawIx,
+  buildClosePositionIx,
+  buildConvertToSharesIx,
+  buildDepositForIx,
   buildDepositIx,
   buildInitializeVaultIx,
+  buildMaxDepositIx,
+  buildMaxWithdrawIx,
+  buildMintSharesIx,
+  buildPreviewDepositIx,
+  buildRequestWithdrawIx,
   buildRouteYieldIx,
+  buildSetMockPriceIx,
+  buildSyncTotalAssetsIx,
+  buildTransferSharesIx,
+  buildWithdrawAssetsIx,
   buildWithdrawIx,
+  decodePythPullPrice,
+  decodeQuoteReturnData,
+  decodeU64ReturnData,
+  decodeUrbaniumEvent,
+  deriveEventAuthorityPda,
   deriveUserPositionPda,
   deriveVaultAuthorityPda,
   deriveVaultPda,
+  deriveVaultTokenAccountPda,
+  deriveWithdrawRequestPda,
+  deriveYieldTokenAccountAPda,
+  deriveYieldTokenAccountBPda,
+  normalizePrice,
+  selectOraclePrice,
+  urbaniumIdl,
 } from "@urbanium/sdk";
 
 // Deterministic, real pubkeys (no random test vectors).
@@ -19,26 +46,47 @@ const user = new PublicKey("11111111111111111111111111111111");
 
 describe("urbanium-sdk", () => {
   it("derives vault + authority PDAs deterministically", () => {
-    const [vault] = deriveVaultPda(programId, mint);
+    const [vault] = deriveVaultPda(programId, mint, user);
     const [vaultAuth] = deriveVaultAuthorityPda(programId, vault);
     const [pos] = deriveUserPositionPda(programId, vault, user);
 
     assert.equal(PublicKey.isOnCurve(vault.toBytes()), false);
     assert.equal(PublicKey.isOnCurve(vaultAuth.toBytes()), false);
     assert.equal(PublicKey.isOnCurve(pos.toBytes()), false);
+
+    const [otherCreatorVault] = deriveVaultPda(programId, mint, programId);
+    assert.notEqual(otherCreatorVault.toBase58(), vault.toBase58());
+  });
+
+  it("derives distinct token account PDAs per role", () => {
+    const [vault] = deriveVaultPda(programId, mint, user);
+    const [vaultAuth] = deriveVaultAuthorityPda(programId, vault);
+
+    const [primary] = deriveVaultTokenAccountPda(programId, vaultAuth);
+    const [yieldA] = deriveYieldTokenAccountAPda(programId, vaultAuth);
+    const [yieldB] = deriveYieldTokenAccountBPda(programId, vaultAuth);
+
+    assert.equal(new Set([primary, yieldA, yieldB].map((k) => k.toBase58())).size, 3);
+    assert.equal(PublicKey.isOnCurve(primary.toBytes()), false);
   });
 
   it("builds instructions with explicit metas", () => {
-    const [vault] = deriveVaultPda(programId, mint);
+    const [vault] = deriveVaultPda(programId, mint, user);
     const [vaultAuthority] = deriveVaultAuthorityPda(programId, vault);
     const [userPosition] = deriveUserPositionPda(programId, vault, user);
 
+    const feeRecipient = new PublicKey("Stake11111111111111111111111111111111111111");
+    const [feePosition] = deriveUserPositionPda(programId, vault, feeRecipient);
+
+    const [vaultTokenAccount] = deriveVaultTokenAccountPda(programId, vaultAuthority);
+    const [yieldA] = deriveYieldTokenAccountAPda(programId, vaultAuthority);
+    const [yieldB] = deriveYieldTokenAccountBPda(programId, vaultAuthority);
+    const [eventAuthority] = deriveEventAuthorityPda(programId);
+
     const payer = user;
-    const vaultTokenAccount = new PublicKey("So11111111111111111111111111111111111111112");
-    const yieldA = new PublicKey("So11111111111111111111111111111111111111112");
-    const yieldB = new PublicKey("So11111111111111111111111111111111111111112");
     const oracleFeed = new PublicKey("So11111111111111111111111111111111111111112");
     const oracleProgram = new PublicKey("So11111111111111111111111111111111111111112");
+    const secondaryFeed = new PublicKey("SysvarC1ock11111111111111111111111111111111");
 
     const initIx = buildInitializeVaultIx(
       programId,
@@ -50,10 +98,11 @@ describe("urbanium-sdk", () => {
         vaultTokenAccount,
         yieldTokenAccountA: yieldA,
         yieldTokenAccountB: yieldB,
+        feePosition,
         oracleFeed,
         systemProgram: SystemProgram.programId,
         tokenProgram: new PublicKey("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"),
-        associatedTokenProgram: new PublicKey("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"),
+        secondaryOracleFeeds: [secondaryFeed],
       },
       {
         oracleProgram,
@@ -61,70 +110,314 @@ describe("urbanium-sdk", () => {
         maxStalenessSeconds: 60n,
         maxConfidenceBps: 200,
         routeThresholdPrice: 0n,
+        decimalsOffset: 6,
+        feeRecipient,
+        managementFeeBps: 200,
+        performanceFeeBps: 1_000,
+        maxTotalAssets: 0n,
+        maxAssetsPerUser: 1_000_000n,
+        depositFeeBps: 10,
+        withdrawFeeBps: 25,
+        minHoldSeconds: 3_600n,
+        earlyExitPenaltyBps: 0,
+        oracleKind: "pythLegacy",
+        priceSource: "ema",
+        secondaryOracles: [
+          {
+            kind: "pythPull",
+            program: oracleProgram,
+            feed: secondaryFeed,
+            feedId: new Uint8Array(32).fill(7),
+          },
+        ],
+        oracleQuorum: 2,
+        oracleAggregation: "median",
+        maxOracleDivergenceBps: 100,
+        conservativeRouting: true,
+        routeLowerPrice: 0n,
+        routeUpperPrice: 0n,
+        oracleFeedId: new Uint8Array(32),
       },
     );
 
     assert.equal(initIx.programId.toBase58(), programId.toBase58());
-    assert.equal(initIx.keys.length, 11);
+    assert.equal(initIx.keys.length, 15);
+    assert.equal(initIx.keys[7]?.pubkey.toBase58(), programId.toBase58());
+    assert.equal(initIx.keys[12]?.pubkey.toBase58(), eventAuthority.toBase58());
+    assert.equal(initIx.keys[13]?.pubkey.toBase58(), programId.toBase58());
+    assert.equal(initIx.keys[14]?.pubkey.toBase58(), secondaryFeed.toBase58());
+
+    const depositAccounts = {
+      depositor: user,
+      mint,
+      vault,
+      vaultAuthority,
+      feePosition,
+      userPosition,
+      userTokenAccount: vaultTokenAccount,
+      vaultTokenAccount,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: new PublicKey("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"),
+      associatedTokenProgram: new PublicKey("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"),
+    };
+
+    const depositIx = buildDepositIx(programId, depositAccounts, 1n, 0n);
+
+    assert.equal(depositIx.keys[0]?.isSigner, true);
+    assert.equal(depositIx.keys[6]?.pubkey.toBase58(), userPosition.toBase58());
 
-    const depositIx = buildDepositIx(
+    // Tokenized vaults take no position.
+    const tokenizedDepositIx = buildDepositIx(
+      programId,
+      { ...depositAccounts, userPosition: undefined },
+      1n,
+      0n,
+    );
+
+    assert.equal(tokenizedDepositIx.keys[6]?.pubkey.toBase58(), programId.toBase58());
+    assert.equal(tokenizedDepositIx.keys.length, depositIx.keys.length);
+
+    const mintSharesIx = buildMintSharesIx(programId, depositAccounts, 1n, 1n);
+
+    assert.deepEqual(mintSharesIx.keys, depositIx.keys);
+    assert.equal(depositIx.keys[1]?.pubkey.toBase58(), user.toBase58());
+
+    const beneficiary = new PublicKey("SysvarC1ock11111111111111111111111111111111");
+    const [beneficiaryPosition] = deriveUserPositionPda(programId, vault, beneficiary);
+    const depositForIx = buildDepositForIx(
+      programId,
+      { ...depositAccounts, beneficiary, userPosition: beneficiaryPosition },
+      1n,
+      0n,
+    );
+
+    assert.equal(depositForIx.keys[1]?.pubkey.toBase58(), beneficiary.toBase58());
+    assert.notDeepEqual(mintSharesIx.data.subarray(0, 8), depositIx.data.subarray(0, 8));
+
+    const withdrawAccounts = {
+      withdrawer: user,
+      owner: user,
+      mint,
+      vault,
+      vaultAuthority,
+      feePosition,
+      userPosition,
+      userTokenAccount: vaultTokenAccount,
+      vaultTokenAccount,
+      yieldTokenAccountA: yieldA,
+      yieldTokenAccountB: yieldB,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: new PublicKey("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"),
+      associatedTokenProgram: new PublicKey("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"),
+    };
+
+    const withdrawIx = buildWithdrawIx(programId, withdrawAccounts, 1n, 0n);
+
+    assert.equal(withdrawIx.keys[0]?.isSigner, true);
+
+    const withdrawAndCloseIx = buildWithdrawIx(programId, withdrawAccounts, 1n, 0n, true);
+
+    assert.equal(withdrawAndCloseIx.data.length, withdrawIx.data.length);
+    assert.notDeepEqual(withdrawAndCloseIx.data, withdrawIx.data);
+
+    const closeIx = buildClosePositionIx(programId, { owner: user, mint, vault, userPosition });
+
+    assert.equal(closeIx.keys[0]?.isWritable, true);
+    assert.equal(closeIx.keys[2]?.isWritable, true);
+    assert.equal(closeIx.keys.length, 6);
+
+    const recipient = new PublicKey("SysvarRent111111111111111111111111111111111");
+    const [recipientPosition] = deriveUserPositionPda(programId, vault, recipient);
+
+    const transferIx = buildTransferSharesIx(
       programId,
       {
-        depositor: user,
+        owner: user,
+        recipient,
         mint,
         vault,
-        vaultAuthority,
         userPosition,
-        userTokenAccount: vaultTokenAccount,
-        vaultTokenAccount,
-        yieldTokenAccountA: yieldA,
-        yieldTokenAccountB: yieldB,
+        recipientPosition,
         systemProgram: SystemProgram.programId,
-        tokenProgram: new PublicKey("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"),
-        associatedTokenProgram: new PublicKey("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"),
       },
       1n,
     );
 
-    assert.equal(depositIx.keys[0]?.isSigner, true);
+    assert.equal(transferIx.keys[5]?.pubkey.toBase58(), recipientPosition.toBase58());
+    assert.equal(transferIx.keys[3]?.isWritable, true);
 
-    const withdrawIx = buildWithdrawIx(
+    const approveIx = buildApproveDelegateIx(
+      programId,
+      { owner: user, mint, vault, userPosition },
+      recipient,
+      1n,
+    );
+
+    assert.equal(approveIx.keys[0]?.isSigner, true);
+
+    const withdrawAssetsIx = buildWithdrawAssetsIx(programId, withdrawAccounts, 1n, 1n);
+
+    assert.deepEqual(withdrawAssetsIx.keys, withdrawIx.keys);
+    assert.notDeepEqual(withdrawAssetsIx.data.subarray(0, 8), withdrawIx.data.subarray(0, 8));
+
+    const routeIx = buildRouteYieldIx(
       programId,
       {
-        withdrawer: user,
+        executor: user,
         mint,
         vault,
         vaultAuthority,
-        userPosition,
-        userTokenAccount: vaultTokenAccount,
+        feePosition,
         vaultTokenAccount,
         yieldTokenAccountA: yieldA,
         yieldTokenAccountB: yieldB,
-        systemProgram: SystemProgram.programId,
+        oracleFeed,
         tokenProgram: new PublicKey("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"),
-        associatedTokenProgram: new PublicKey("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"),
       },
       1n,
     );
 
-    assert.equal(withdrawIx.keys[0]?.isSigner, true);
+    assert.equal(routeIx.keys[0]?.isSigner, true);
 
-    const routeIx = buildRouteYieldIx(
+    const syncIx = buildSyncTotalAssetsIx(programId, {
+      mint,
+      vault,
+      vaultAuthority,
+      vaultTokenAccount,
+      yieldTokenAccountA: yieldA,
+      yieldTokenAccountB: yieldB,
+    });
+
+    assert.equal(syncIx.keys.every((k) => !k.isSigner), true);
+
+    const accrueIx = buildAccrueFeesIx(programId, { mint, vault, feePosition });
+
+    assert.equal(accrueIx.keys.every((k) => !k.isSigner), true);
+    assert.equal(accrueIx.keys.length, 5);
+    assert.equal(accrueIx.keys[3]?.pubkey.toBase58(), eventAuthority.toBase58());
+
+    const previewIx = buildPreviewDepositIx(programId, { mint, vault }, 1n);
+    const convertIx = buildConvertToSharesIx(programId, { mint, vault }, 1n);
+
+    assert.equal(previewIx.keys.some((k) => k.isSigner || k.isWritable), false);
+    assert.notDeepEqual(previewIx.data.subarray(0, 8), convertIx.data.subarray(0, 8));
+
+    const maxWithdrawIx = buildMaxWithdrawIx(
       programId,
       {
-        executor: user,
         mint,
         vault,
         vaultAuthority,
+        userPosition,
         vaultTokenAccount,
         yieldTokenAccountA: yieldA,
         yieldTokenAccountB: yieldB,
-        oracleFeed,
+      },
+      user,
+    );
+
+    assert.equal(maxWithdrawIx.keys[4]?.pubkey.toBase58(), programId.toBase58());
+
+    const maxDepositIx = buildMaxDepositIx(programId, { mint, vault, userPosition }, user);
+
+    assert.equal(maxDepositIx.keys.length, 3);
+    assert.equal(maxDepositIx.keys[2]?.pubkey.toBase58(), userPosition.toBase58());
+    assert.equal(decodeU64ReturnData(Buffer.from([1, 0, 0, 0, 0, 0, 0, 0])), 1n);
+    assert.equal(decodeUrbaniumEvent(Buffer.from([1, 0, 0, 0, 0, 0, 0, 0])), null);
+    assert.deepEqual(
+      decodeQuoteReturnData(Buffer.from([5, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0])),
+      { amount: 5n, fee: 2n },
+    );
+
+    const [firstRequest] = deriveWithdrawRequestPda(programId, vault, 0n);
+    const [secondRequest] = deriveWithdrawRequestPda(programId, vault, 1n);
+
+    assert.notEqual(firstRequest.toBase58(), secondRequest.toBase58());
+
+    const requestIx = buildRequestWithdrawIx(
+      programId,
+      {
+        owner: user,
+        mint,
+        vault,
+        feePosition,
+        userPosition,
+        withdrawRequest: firstRequest,
+        systemProgram: SystemProgram.programId,
         tokenProgram: new PublicKey("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"),
       },
       1n,
+      1n,
     );
 
-    assert.equal(routeIx.keys[0]?.isSigner, true);
+    assert.equal(requestIx.keys.length, 12);
+    assert.equal(requestIx.keys[6]?.pubkey.toBase58(), programId.toBase58());
+    assert.equal(requestIx.keys[10]?.pubkey.toBase58(), eventAuthority.toBase58());
+    assert.equal(requestIx.keys[11]?.pubkey.toBase58(), programId.toBase58());
+
+    const claimIx = buildClaimWithdrawIx(programId, {
+      owner: user,
+      mint,
+      vault,
+      vaultAuthority,
+      withdrawRequest: firstRequest,
+      userTokenAccount: user,
+      vaultTokenAccount,
+      yieldTokenAccountA: yieldA,
+      yieldTokenAccountB: yieldB,
+      tokenProgram: new PublicKey("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"),
+    });
+
+    assert.equal(claimIx.keys.every((k) => !k.isSigner), true);
+    assert.equal(claimIx.keys.length, 12);
+  });
+
+  it("decodes Pyth pull price updates", () => {
+    const data = Buffer.alloc(8 + 32 + 1 + 32 + 8 + 8 + 4 + 8 + 8 + 8 + 8 + 8);
+    data.writeUInt8(1, 40);
+    data.writeBigInt64LE(12_345n, 73);
+    data.writeBigUInt64LE(7n, 81);
+    data.writeInt32LE(-8, 89);
+    data.writeBigInt64LE(1_700_000_000n, 93);
+    data.writeBigInt64LE(12_000n, 109);
+    data.writeBigUInt64LE(9n, 117);
+
+    const pulled = decodePythPullPrice(data);
+
+    assert.deepEqual(pulled, {
+      price: 12_345n,
+      conf: 7n,
+      expo: -8,
+      publishTime: 1_700_000_000,
+      ema: { price: 12_000n, conf: 9n },
+    });
+    assert.equal(selectOraclePrice(pulled, "min").price, 12_000n);
+    assert.equal(selectOraclePrice(pulled, "max").conf, 7n);
+    assert.equal(normalizePrice(12_000n, -8), 120_000_000_000_000n);
+    assert.equal(normalizePrice(-7n, -20), 0n);
+
+    data.writeUInt8(0, 40);
+    assert.throws(() => decodePythPullPrice(data));
+  });
+
+  it("builds mock oracle instructions", () => {
+    const mockPrice = new PublicKey("SysvarC1ock11111111111111111111111111111111");
+    const setIx = buildSetMockPriceIx(
+      programId,
+      { authority: user, mockPrice },
+      100n,
+      1n,
+      -8,
+      0n,
+      90n,
+      2n,
+    );
+
+    assert.equal(setIx.keys[0]?.isSigner, true);
+    assert.equal(setIx.keys[1]?.isWritable, true);
+    assert.equal(setIx.data.length, 8 + 8 + 8 + 4 + 8 + 8 + 8);
+    // Release builds leave the mock instructions out of the IDL.
+    const releaseIxs: string[] = urbaniumIdl.instructions.map((ix) => ix.name);
+    assert.equal(releaseIxs.includes("setMockPrice"), false);
   });
 });